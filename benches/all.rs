@@ -1,4 +1,4 @@
-use swift_check::{any, arch::load, eq, ensure, range, find, for_all_ensure, search, for_all_ensure_ct, one_of};
+use swift_check::{any, arch::load, eq, ensure, range, find, for_all_ensure, search, for_all_ensure_ct, one_of, search_all, find_substring, Finder, byte_set};
 use criterion::{Criterion, Throughput, criterion_group, criterion_main, black_box};
 use swift_check::not;
 use swift_check::require::check;
@@ -51,6 +51,8 @@ fn bench_aligned(c: &mut Criterion) {
         })
     });
     g.bench_function("std/is-lowercase-alphabet", |b| {
+        // the hand written scalar check being compared against
+        #[allow(clippy::manual_is_ascii_check)]
         b.iter(|| {
             let legal = black_box(inp).iter().all(|&c| matches!(c, b'a'..=b'z'));
             black_box(legal)
@@ -63,6 +65,8 @@ fn bench_aligned(c: &mut Criterion) {
         })
     });
     g.bench_function("std/is-alphabet", |b| {
+        // the hand written scalar check being compared against
+        #[allow(clippy::manual_is_ascii_check)]
         b.iter(|| {
             let legal = black_box(inp).iter().all(|&c| matches!(c, b'a'..=b'z' | b'A'..=b'Z'));
             black_box(legal)
@@ -104,11 +108,44 @@ fn bench_multi(c: &mut Criterion) {
         })
     });
 
+    g.bench_function("simd/search-all", |b| {
+        b.iter(|| {
+            let res = search_all(black_box(input), eq(b' ')).count();
+            black_box(res)
+        })
+    });
+
+    g.bench_function("memchr/memchr-iter", |b| {
+        b.iter(|| {
+            let res = memchr::memchr_iter(b' ', black_box(input)).count();
+            black_box(res)
+        })
+    });
+
     g.bench_function("memchr/find-at-end", |b| {
         b.iter(|| {
             assert_eq!(memchr::memchr(b'6', black_box(input)), Some(127));
         })
     });
+
+    g.bench_function("simd/ensure-base64-any", |b| {
+        b.iter(|| {
+            let res = for_all_ensure(black_box(input), any!(
+                range!(b'A'..=b'Z'), range!(b'a'..=b'z'), range!(b'0'..=b'9'),
+                eq(b'+'), eq(b'/'), eq(b'='), eq(b' '), eq(b',')
+            ));
+            black_box(res)
+        })
+    });
+
+    g.bench_function("simd/ensure-base64-byte-set", |b| {
+        b.iter(|| {
+            let res = for_all_ensure(black_box(input), byte_set!(
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'+' | b'/' | b'=' | b' ' | b','
+            ));
+            black_box(res)
+        })
+    });
 }
 
 fn bench_remainder(c: &mut Criterion) {
@@ -235,5 +272,61 @@ fn bench_massive(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, bench_massive, bench_multi, bench_remainder, bench_partial, bench_aligned);
+fn bench_substring(c: &mut Criterion) {
+    let mut input = [b'-'; 65536];
+    let end = input.len() - 12;
+    input[end..].copy_from_slice(b"--boundary--");
+
+    let mut g = c.benchmark_group("substring");
+    g.throughput(Throughput::Bytes(input.len() as u64));
+
+    g.bench_function("simd/find-at-end", |b| {
+        b.iter(|| {
+            let res = find_substring(black_box(&input), b"--boundary");
+            assert_eq!(res, Some(input.len() - 12));
+        })
+    });
+
+    g.bench_function("memchr/find-at-end", |b| {
+        b.iter(|| {
+            let res = memchr::memmem::find(black_box(&input), b"--boundary");
+            assert_eq!(res, Some(input.len() - 12));
+        })
+    });
+
+    let finder = Finder::new(b"--boundary");
+    let memmem_finder = memchr::memmem::Finder::new(b"--boundary");
+
+    g.bench_function("simd/finder-at-end", |b| {
+        b.iter(|| {
+            let res = finder.find(black_box(&input));
+            assert_eq!(res, Some(input.len() - 12));
+        })
+    });
+
+    g.bench_function("memchr/finder-at-end", |b| {
+        b.iter(|| {
+            let res = memmem_finder.find(black_box(&input));
+            assert_eq!(res, Some(input.len() - 12));
+        })
+    });
+
+    let page = b"<html><head><script>let x = 1;</script></head><body><p>hello world</p></body></html>";
+
+    g.bench_function("simd/find-short", |b| {
+        b.iter(|| {
+            let res = find_substring(black_box(page), b"</script>");
+            black_box(res)
+        })
+    });
+
+    g.bench_function("memchr/find-short", |b| {
+        b.iter(|| {
+            let res = memchr::memmem::find(black_box(page), b"</script>");
+            black_box(res)
+        })
+    });
+}
+
+criterion_group!(benches, bench_massive, bench_substring, bench_multi, bench_remainder, bench_partial, bench_aligned);
 criterion_main!(benches);
\ No newline at end of file
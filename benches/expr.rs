@@ -0,0 +1,89 @@
+use swift_check::{any, eq, range, not, for_all_ensure, count, Condition, Expr};
+use criterion::{Criterion, Throughput, criterion_group, criterion_main, black_box};
+
+fn bench_expr(c: &mut Criterion) {
+    let input = b"Hello world I am an input with the numbers at the end, for this bench my \
+    length is divisible by                              016";
+
+    let mut g = c.benchmark_group("expr");
+    g.throughput(Throughput::Bytes(input.len() as u64));
+
+    // the written condition checks each range, the expression simplifies to a single range
+    let split = Expr::Or(vec![
+        Expr::Range(b' ', b'@'), Expr::Range(b'A', b'Z'), Expr::Range(b'[', b'`'), Expr::Range(b'a', b'z')
+    ]).compile();
+
+    g.bench_function("written/merged-ranges", |b| {
+        b.iter(|| {
+            let res = for_all_ensure(black_box(input), any!(
+                range!(b' '..=b'@'), range!(b'A'..=b'Z'), range!(b'['..=b'`'), range!(b'a'..=b'z')
+            ));
+            black_box(res)
+        })
+    });
+
+    g.bench_function("compiled/merged-ranges", |b| {
+        b.iter(|| {
+            let res = for_all_ensure(black_box(input), split.by_ref());
+            black_box(res)
+        })
+    });
+
+    let text = Expr::Or(vec![
+        Expr::Range(b'A', b'Z'), Expr::Range(b'a', b'z'), Expr::Range(b'0', b'9'),
+        Expr::Eq(b' '), Expr::Eq(b','), Expr::not(Expr::not(Expr::Eq(b'.')))
+    ]).compile();
+
+    g.bench_function("written/text", |b| {
+        b.iter(|| {
+            let res = for_all_ensure(black_box(input), any!(
+                range!(b'A'..=b'Z'), range!(b'a'..=b'z'), range!(b'0'..=b'9'),
+                eq(b' '), eq(b','), not(not(eq(b'.')))
+            ));
+            black_box(res)
+        })
+    });
+
+    g.bench_function("compiled/text", |b| {
+        b.iter(|| {
+            let res = for_all_ensure(black_box(input), text.by_ref());
+            black_box(res)
+        })
+    });
+
+    // a nibble lookup where the backend has a native shuffle, otherwise a comparison for each run
+    let base64 = Expr::Or(vec![
+        Expr::Range(b'A', b'Z'), Expr::Range(b'a', b'z'), Expr::Range(b'0', b'9'),
+        Expr::Eq(b'+'), Expr::Eq(b'/'), Expr::Eq(b'='), Expr::Eq(b' '), Expr::Eq(b',')
+    ]).compile();
+
+    g.bench_function("written/base64", |b| {
+        b.iter(|| {
+            let res = for_all_ensure(black_box(input), any!(
+                range!(b'A'..=b'Z'), range!(b'a'..=b'z'), range!(b'0'..=b'9'),
+                eq(b'+'), eq(b'/'), eq(b'='), eq(b' '), eq(b',')
+            ));
+            black_box(res)
+        })
+    });
+
+    g.bench_function("compiled/base64", |b| {
+        b.iter(|| {
+            let res = for_all_ensure(black_box(input), base64.by_ref());
+            black_box(res)
+        })
+    });
+
+    // too many runs to compare for each, a nibble lookup on every backend
+    let alternate = Expr::Or((b' '..=b'~').step_by(2).map(Expr::Eq).collect()).compile();
+
+    g.bench_function("compiled/nibbles", |b| {
+        b.iter(|| {
+            let res = count(black_box(input), alternate.by_ref());
+            black_box(res)
+        })
+    });
+}
+
+criterion_group!(benches, bench_expr);
+criterion_main!(benches);
@@ -0,0 +1,7 @@
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    // the single colon form, `cargo::` is only understood from Cargo 1.77. Older releases treat
+    // this as metadata and ignore it
+    println!("cargo:rustc-check-cfg=cfg(mirai)");
+    println!("cargo:rustc-check-cfg=cfg(docsrs)");
+}
@@ -3,10 +3,12 @@
 use core::arch::aarch64::{
     uint8x16_t,
     vandq_u8, vceqq_u8, vcgeq_u8, vcgtq_u8, vcleq_u8, vcltq_u8, vdupq_n_u8, veorq_u8, vget_lane_u64,
-    vld1q_u8, vmvnq_u8, vorrq_u8, vreinterpret_u64_u8, vreinterpretq_u16_u8, vshrn_n_u16
+    vld1q_u8, vmvnq_u8, vorrq_u8, vqtbl1q_u8, vreinterpret_u64_u8, vreinterpretq_u16_u8, vshrn_n_u16,
+    vshrq_n_u8, vsubq_u8, vqsubq_u8
 };
 use core::arch::aarch64::vld1q_lane_u8;
 
+pub const WIDTH: usize = 16;
 pub type Vector = uint8x16_t;
 pub type Ptr = u8;
 pub const STEP: usize = 16;
@@ -38,6 +40,33 @@ impl MoveMask {
     pub const fn trailing_ones(&self) -> u32 {
         self.0.trailing_ones() >> 2
     }
+    /// The number of set lanes
+    #[inline(always)] #[must_use]
+    pub const fn count_ones(&self) -> u32 {
+        self.0.count_ones() >> 2
+    }
+    /// The number of unset lanes preceding the highest set lane
+    #[inline(always)] #[must_use]
+    pub const fn leading_zeros(&self) -> u32 {
+        self.0.leading_zeros() >> 2
+    }
+    /// Unset the lowest lane which is set
+    #[inline(always)] #[must_use]
+    pub const fn clear_lowest(&self) -> Self {
+        // each lane is represented by a nibble, isolate the lowest bit and widen it to the nibble
+        let lowest = self.0 & self.0.wrapping_neg();
+        Self(self.0 & !lowest.wrapping_mul(0xF))
+    }
+    /// Unset every lane below `lane`
+    #[inline(always)] #[must_use]
+    pub const fn lanes_from(&self, lane: u32) -> Self {
+        if lane >= 16 { Self(0) } else { Self(self.0 & (u64::MAX << (lane << 2))) }
+    }
+    /// Unset every lane at or above `lane`
+    #[inline(always)] #[must_use]
+    pub const fn lanes_below(&self, lane: u32) -> Self {
+        if lane >= 16 { Self(self.0) } else { Self(self.0 & !(u64::MAX << (lane << 2))) }
+    }
 }
 
 impl_bit_ops!(MoveMask);
@@ -86,6 +115,35 @@ pub unsafe fn less_than(a: Vector, b: Vector) -> Vector {
 #[inline(always)] #[must_use]
 pub unsafe fn splat(a: u8) -> Vector { vdupq_n_u8(a) }
 
+#[inline(always)] #[must_use]
+pub unsafe fn sub(a: Vector, b: Vector) -> Vector { vsubq_u8(a, b) }
+
+#[inline(always)] #[must_use]
+pub unsafe fn saturating_sub(a: Vector, b: Vector) -> Vector { vqsubq_u8(a, b) }
+
+#[inline(always)] #[must_use]
+pub unsafe fn high_nibbles(a: Vector) -> Vector { vshrq_n_u8::<4>(a) }
+
+/// Select the byte of the `table` at each index of `idx`, each index must be below 16
+#[inline(always)] #[must_use]
+pub unsafe fn shuffle(table: Vector, idx: Vector) -> Vector { vqtbl1q_u8(table, idx) }
+
+/// Shift each byte of `data` up `N` lanes, filling the lowest `N` lanes with the highest `N` lanes
+/// of `prev`, `N` must be within `1..16`
+#[inline(always)] #[must_use]
+pub unsafe fn shift_in<const N: usize>(data: Vector, prev: Vector) -> Vector {
+    // the extraction takes an immediate, so each `N` is spelled out
+    macro_rules! shift {
+        ($($n:literal),*) => {
+            match N {
+                $($n => core::arch::aarch64::vextq_u8::<{16 - $n}>(prev, data),)*
+                _ => unreachable!("shifts must be within 1..16")
+            }
+        };
+    }
+    shift!(1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15)
+}
+
 #[inline(always)] #[must_use]
 pub unsafe fn load_unchecked(data: *const Ptr) -> Vector {
     vld1q_u8(data)
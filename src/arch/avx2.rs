@@ -0,0 +1,483 @@
+#![allow(clippy::missing_safety_doc)]
+
+use core::arch::x86_64::{
+    __m256i,
+    _mm256_and_si256, _mm256_cmpeq_epi8, _mm256_cmpgt_epi8, _mm256_load_si256, _mm256_or_si256,
+    _mm256_set1_epi8, _mm256_xor_si256, _mm256_srli_epi16, _mm256_sub_epi8, _mm256_subs_epu8,
+    _mm256_shuffle_epi8, _mm256_broadcastsi128_si256, _mm_loadu_si128
+};
+
+cfg_runtime!(
+    use core::arch::x86_64::{
+        _mm256_movemask_epi8, _mm256_loadu_si256,
+    };
+);
+
+cfg_dispatch!(
+    use core::arch::x86_64::{
+        __m128i, _mm256_castsi128_si256, _mm256_castsi256_si128, _mm256_extracti128_si256,
+        _mm256_inserti128_si256
+    };
+);
+
+cfg_verify!(
+    // the crate's `is_aligned` takes the vectors of the backend selected at compile time, which
+    // are SSE2 where this backend is selected at runtime
+    pub(crate) fn is_aligned(ptr: *const Ptr) -> bool {
+        byte_ptr(ptr).align_offset(WIDTH) == 0
+    }
+
+    use mirai_annotations::{checked_precondition};
+
+    // foreign specifications, these are pure, so we only have to inform `mirai` of their existence.
+    fn _mm256_movemask_epi8(_input: Vector) -> i32  {
+        mirai_annotations::result!()
+    }
+
+    fn _mm256_loadu_si256(_ptr: *const Ptr) -> Vector {
+        mirai_annotations::result!()
+    }
+);
+
+pub const WIDTH: usize = 32;
+pub type Vector = __m256i;
+pub type Ptr = Vector;
+pub const STEP: usize = 1;
+pub const STEP_SIZE: usize = 32;
+
+#[repr(transparent)]
+pub struct MoveMask(u32);
+impl MoveMask {
+    pub const MAX_TRAIL: u32 = 32;
+
+    #[inline(always)] #[must_use]
+    pub unsafe fn new(input: Vector) -> Self {
+        Self(_mm256_movemask_epi8(input) as u32)
+    }
+    #[inline(always)] #[must_use]
+    pub const fn all_bits_set(&self) -> bool {
+        self.0 == u32::MAX
+    }
+    #[inline(always)] #[must_use]
+    pub const fn any_bit_set(&self) -> bool { self.0 > 0 }
+    #[inline(always)] #[must_use]
+    pub const fn trailing_zeros(&self) -> u32 {
+        self.0.trailing_zeros()
+    }
+    #[inline(always)] #[must_use]
+    pub const fn trailing_ones(&self) -> u32 {
+        self.0.trailing_ones()
+    }
+    /// The number of set lanes
+    #[inline(always)] #[must_use]
+    pub const fn count_ones(&self) -> u32 {
+        self.0.count_ones()
+    }
+    /// The number of unset lanes preceding the highest set lane
+    #[inline(always)] #[must_use]
+    pub const fn leading_zeros(&self) -> u32 {
+        self.0.leading_zeros()
+    }
+    /// Unset the lowest lane which is set
+    #[inline(always)] #[must_use]
+    pub const fn clear_lowest(&self) -> Self {
+        Self(self.0 & self.0.wrapping_sub(1))
+    }
+    /// Unset every lane below `lane`
+    #[inline(always)] #[must_use]
+    pub const fn lanes_from(&self, lane: u32) -> Self {
+        if lane >= 32 { Self(0) } else { Self(self.0 & (u32::MAX << lane)) }
+    }
+    /// Unset every lane at or above `lane`
+    #[inline(always)] #[must_use]
+    pub const fn lanes_below(&self, lane: u32) -> Self {
+        if lane >= 32 { Self(self.0) } else { Self(self.0 & !(u32::MAX << lane)) }
+    }
+}
+
+impl_bit_ops!(MoveMask);
+
+#[inline(always)] #[must_use]
+pub unsafe fn eq(a: Vector, b: Vector) -> Vector { _mm256_cmpeq_epi8(a, b) }
+
+// Bitwise XOR with all bits set to simulate NOT
+#[inline(always)] #[must_use]
+pub unsafe fn not(a: Vector) -> Vector { xor(a, _mm256_set1_epi8(-1)) }
+
+#[inline(always)] #[must_use]
+pub unsafe fn xor(a: Vector, b: Vector) -> Vector { _mm256_xor_si256(a, b) }
+
+#[inline(always)] #[must_use]
+pub unsafe fn or(a: Vector, b: Vector) -> Vector { _mm256_or_si256(a, b) }
+
+#[inline(always)] #[must_use]
+pub unsafe fn and(a: Vector, b: Vector) -> Vector { _mm256_and_si256(a, b) }
+
+// compute via compliment as avx2 lacks gt eq
+#[inline(always)] #[must_use]
+pub unsafe fn greater_than_or_eq(a: Vector, b: Vector) -> Vector { not(less_than(a, b)) }
+
+#[inline(always)] #[must_use]
+pub unsafe fn greater_than(a: Vector, b: Vector) -> Vector { _mm256_cmpgt_epi8(a, b) }
+
+// compute via compliment as avx2 lacks lt eq
+#[inline(always)] #[must_use]
+pub unsafe fn less_than_or_eq(a: Vector, b: Vector) -> Vector { not(greater_than(a, b)) }
+
+// avx2 lacks lt, swap the operands of gt
+#[inline(always)] #[must_use]
+pub unsafe fn less_than(a: Vector, b: Vector) -> Vector { _mm256_cmpgt_epi8(b, a) }
+
+#[inline(always)] #[must_use]
+pub unsafe fn splat(a: u8) -> Vector { _mm256_set1_epi8(a as i8) }
+
+#[inline(always)] #[must_use]
+pub unsafe fn sub(a: Vector, b: Vector) -> Vector { _mm256_sub_epi8(a, b) }
+
+#[inline(always)] #[must_use]
+pub unsafe fn saturating_sub(a: Vector, b: Vector) -> Vector { _mm256_subs_epu8(a, b) }
+
+// avx2 lacks 8 bit shifts, shift the 16 bit lanes and clear what crossed over from the neighbour
+#[inline(always)] #[must_use]
+pub unsafe fn high_nibbles(a: Vector) -> Vector { and(_mm256_srli_epi16::<4>(a), splat(0x0F)) }
+
+/// Select the byte of the `table` at each index of `idx`, each index must be below 16
+///
+/// The shuffle does not cross the 128 bit lanes, so the `table` must be repeated in each lane, as
+/// [`load`] does.
+#[inline(always)] #[must_use]
+pub unsafe fn shuffle(table: Vector, idx: Vector) -> Vector { _mm256_shuffle_epi8(table, idx) }
+
+/// Shift each byte of `data` up `N` lanes, filling the lowest `N` lanes with the highest `N` lanes
+/// of `prev`, `N` must be within `1..16`
+#[inline(always)] #[must_use]
+pub unsafe fn shift_in<const N: usize>(data: Vector, prev: Vector) -> Vector {
+    // the byte alignment does not cross the 128 bit lanes, so each lane is aligned against the
+    // lane preceding it: the high lane of `prev` for the low lane, the low lane of `data` for the
+    // high lane.
+    let behind = core::arch::x86_64::_mm256_permute2x128_si256::<0x21>(prev, data);
+    macro_rules! shift {
+        ($($n:literal),*) => {
+            match N {
+                $($n => core::arch::x86_64::_mm256_alignr_epi8::<{16 - $n}>(data, behind),)*
+                _ => unreachable!("shifts must be within 1..16")
+            }
+        };
+    }
+    shift!(1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15)
+}
+
+#[inline(always)] #[must_use]
+pub unsafe fn load_unchecked(ptr: *const Ptr) -> Vector {
+    _mm256_loadu_si256(ptr)
+}
+
+/// # Safety
+///
+/// The pointer must be aligned to the register width.
+#[cfg_attr(feature = "verify", contracts::requires(is_aligned(ptr)))]
+#[inline(always)] #[must_use]
+pub unsafe fn load_aligned(ptr: *const Ptr) -> Vector {
+    _mm256_load_si256(ptr)
+}
+
+#[inline(always)] #[must_use]
+pub unsafe fn maybe_aligned_load(ptr: *const u8) -> Vector {
+    if ptr.align_offset(WIDTH) == 0 {
+        unsafe { load_aligned(simd_ptr(ptr)) }
+    } else {
+        unsafe { load_unchecked(simd_ptr(ptr)) }
+    }
+}
+
+/// Load 16 bytes into both 128 bit lanes of a register
+///
+/// This keeps `load` and the lower-level api taking 16 bytes regardless of the backend, as each
+/// lane holds the same bytes, `ensure!` and `find!` give the same results as the 16 byte backends.
+#[inline(always)] #[must_use]
+pub fn load(data: &[u8; 16]) -> Vector {
+    // SAFETY: the length is ensured by the type
+    unsafe { _mm256_broadcastsi128_si256(_mm_loadu_si128(data.as_ptr().cast())) }
+}
+
+cfg_runtime!(
+    #[inline(always)] #[must_use]
+    pub const fn byte_ptr(ptr: *const Ptr) -> *const u8 {
+        ptr.cast()
+    }
+
+    #[inline(always)] #[must_use]
+    pub const fn simd_ptr(ptr: *const u8) -> *const Ptr {
+        ptr.cast()
+    }
+);
+
+cfg_verify!(
+    #[inline(always)] #[must_use]
+    pub fn byte_ptr(ptr: *const Ptr) -> *const u8 {
+        let ret = ptr.cast();
+        contract!(postcondition!(simd_ptr(ret) == ptr));
+        ret
+    }
+
+    #[inline(always)] #[must_use]
+    pub fn simd_ptr(ptr: *const u8) -> *const Ptr {
+        let ret = ptr.cast();
+        contract!(postcondition!(byte_ptr(ret) == ptr));
+        ret
+    }
+);
+
+/// Load under 32 bytes into a SIMD register
+///
+/// This initializes the register with zeroes, copying however many bytes were passed onto the
+/// stack and loading from there.
+///
+/// # Safety
+///
+/// If the count is greater than the data's length you'll CVE 125 yourself.
+#[inline] #[must_use]
+pub unsafe fn load_partial(data: &[u8], count: usize) -> Vector {
+    debug_assert_eq!(data.len(), count);
+    debug_assert!(count <= WIDTH);
+
+    let mut buf = [0u8; WIDTH];
+    buf.get_unchecked_mut(..count).copy_from_slice(data.get_unchecked(..count));
+    load_unchecked(simd_ptr(buf.as_ptr()))
+}
+
+cfg_dispatch!(
+    /// Split the `data` into its low and high 128 bit lanes
+    #[inline(always)] #[must_use]
+    pub unsafe fn split(data: Vector) -> (__m128i, __m128i) {
+        (_mm256_castsi256_si128(data), _mm256_extracti128_si256::<1>(data))
+    }
+
+    /// Join the `low` and `high` 128 bit lanes into one vector, the inverse of [`split`]
+    #[inline(always)] #[must_use]
+    pub unsafe fn join(low: __m128i, high: __m128i) -> Vector {
+        _mm256_inserti128_si256::<1>(_mm256_castsi128_si256(low), high)
+    }
+);
+
+// where this backend is selected at runtime, the tests may run on CPUs without AVX2
+#[cfg(all(test, not(mirai), target_feature = "avx2"))]
+mod tests {
+    use super::*;
+    use crate::arch::x86_64;
+    use quickcheck::quickcheck;
+
+    #[cfg(not(feature = "ensure-simd"))]
+    use crate::arch::fallback;
+
+    fn bytes(v: Vector) -> [u8; 32] {
+        unsafe { core::mem::transmute::<Vector, [u8; 32]>(v) }
+    }
+
+    fn sse_bytes(halves: [x86_64::Vector; 2]) -> [u8; 32] {
+        unsafe { core::mem::transmute::<[x86_64::Vector; 2], [u8; 32]>(halves) }
+    }
+
+    #[cfg(not(feature = "ensure-simd"))]
+    fn fallback_bytes(halves: [fallback::Vector; 2]) -> [u8; 32] {
+        let mut res = [0u8; 32];
+        res[..16].copy_from_slice(&halves[0].to_le_bytes());
+        res[16..].copy_from_slice(&halves[1].to_le_bytes());
+        res
+    }
+
+    fn halves(data: &[u8; 32]) -> (&[u8; 16], &[u8; 16]) {
+        let (lo, hi) = data.split_at(16);
+        (lo.try_into().unwrap(), hi.try_into().unwrap())
+    }
+
+    /// Compare each op against sse2 applied to each half
+    macro_rules! compare_sse {
+        ($a:ident, $b:ident, $($op:ident),* $(,)?) => {{
+            let (a, b) = (load_unchecked(simd_ptr($a.as_ptr())), load_unchecked(simd_ptr($b.as_ptr())));
+            let ((a_lo, a_hi), (b_lo, b_hi)) = (halves(&$a), halves(&$b));
+            $(
+                let sse = [
+                    x86_64::$op(x86_64::load(a_lo), x86_64::load(b_lo)),
+                    x86_64::$op(x86_64::load(a_hi), x86_64::load(b_hi))
+                ];
+                if bytes($op(a, b)) != sse_bytes(sse) { return false; }
+            )*
+        }};
+    }
+
+    /// Compare each op against the fallback applied to each half, after normalizing each byte.
+    ///
+    /// The fallback sets true lanes to `1` rather than `0xFF`, and compares unsigned where x86
+    /// compares signed, so only some ops can be compared against it.
+    macro_rules! compare_fallback {
+        ($a:ident, $b:ident, $norm:expr, $($op:ident),* $(,)?) => {
+            #[cfg(not(feature = "ensure-simd"))]
+            {
+                let (a, b) = (load_unchecked(simd_ptr($a.as_ptr())), load_unchecked(simd_ptr($b.as_ptr())));
+                let ((a_lo, a_hi), (b_lo, b_hi)) = (halves(&$a), halves(&$b));
+                $(
+                    let scalar = [
+                        fallback::$op(fallback::load(a_lo), fallback::load(b_lo)),
+                        fallback::$op(fallback::load(a_hi), fallback::load(b_hi))
+                    ];
+                    if bytes($op(a, b)).map($norm) != fallback_bytes(scalar).map($norm) {
+                        return false;
+                    }
+                )*
+            }
+        };
+    }
+
+    quickcheck! {
+        fn binary_ops_match_narrower(a: Vec<u8>, b: Vec<u8>) -> bool {
+            let (mut lhs, mut rhs) = ([0u8; 32], [0u8; 32]);
+            lhs.iter_mut().zip(a.iter().cycle()).for_each(|(dst, src)| *dst = *src);
+            rhs.iter_mut().zip(b.iter().chain(a.iter()).cycle()).for_each(|(dst, src)| *dst = *src);
+            unsafe {
+                compare_sse!(
+                    lhs, rhs,
+                    eq, and, or, xor, sub, saturating_sub,
+                    greater_than, greater_than_or_eq, less_than, less_than_or_eq
+                );
+                compare_fallback!(lhs, rhs, |byte| byte, and, or, xor, sub, saturating_sub);
+                compare_fallback!(lhs, rhs, |byte| byte != 0, eq);
+            }
+            true
+        }
+        fn nibble_ops_match_narrower(table: Vec<u8>, a: Vec<u8>) -> bool {
+            let mut lhs = [0u8; 32];
+            let mut tbl = [0u8; 16];
+            lhs.iter_mut().zip(a.iter().cycle()).for_each(|(dst, src)| *dst = *src);
+            tbl.iter_mut().zip(table.iter().cycle()).for_each(|(dst, src)| *dst = *src);
+            let (lo, hi) = halves(&lhs);
+            unsafe {
+                let data = load_unchecked(simd_ptr(lhs.as_ptr()));
+                let high = high_nibbles(data);
+                let shuffled = bytes(shuffle(load(&tbl), and(data, splat(0x0F))));
+
+                let sse_high = [x86_64::high_nibbles(x86_64::load(lo)), x86_64::high_nibbles(x86_64::load(hi))];
+                let sse_shuffled = [
+                    x86_64::shuffle(x86_64::load(&tbl), x86_64::and(x86_64::load(lo), x86_64::splat(0x0F))),
+                    x86_64::shuffle(x86_64::load(&tbl), x86_64::and(x86_64::load(hi), x86_64::splat(0x0F)))
+                ];
+
+                bytes(high) == sse_bytes(sse_high) && shuffled == sse_bytes(sse_shuffled)
+            }
+        }
+        fn move_mask_matches_narrower(a: Vec<u8>, lane: u8) -> bool {
+            let mut data = [0u8; 32];
+            data.iter_mut().zip(a.iter().cycle()).for_each(|(dst, src)| *dst = *src);
+            let (lo, hi) = halves(&data);
+            let lane = lane as u32 % 33;
+            unsafe {
+                let wide = MoveMask::new(load_unchecked(simd_ptr(data.as_ptr())));
+                let sse = [x86_64::MoveMask::new(x86_64::load(lo)), x86_64::MoveMask::new(x86_64::load(hi))];
+                let (lo_ones, hi_ones) = (sse[0].count_ones(), sse[1].count_ones());
+                let expected_tz = if sse[0].any_bit_set() {
+                    sse[0].trailing_zeros()
+                } else {
+                    16 + sse[1].trailing_zeros().min(16)
+                };
+                wide.count_ones() == lo_ones + hi_ones
+                    && wide.trailing_zeros() == expected_tz
+                    && wide.lanes_from(lane).count_ones() + wide.lanes_below(lane).count_ones()
+                        == wide.count_ones()
+            }
+        }
+    }
+
+    /// Each position of the `input` meeting `'a' or '0'..='9'`, evaluated 16 bytes at a time with
+    /// the narrower backend `$arch`
+    macro_rules! narrow_matches {
+        ($arch:ident, $input:expr) => {{
+            let mut found = Vec::new();
+            for (i, chunk) in $input.chunks(16).enumerate() {
+                unsafe {
+                    let data = $arch::load_partial(chunk, chunk.len());
+                    let digit = $arch::and(
+                        $arch::greater_than_or_eq(data, $arch::splat(b'0')),
+                        $arch::less_than_or_eq(data, $arch::splat(b'9'))
+                    );
+                    let met = $arch::or($arch::eq(data, $arch::splat(b'a')), digit);
+                    let mut mask = $arch::MoveMask::new(met).lanes_below(chunk.len() as u32);
+                    while mask.any_bit_set() {
+                        found.push(i * 16 + mask.trailing_zeros() as usize);
+                        mask = mask.clear_lowest();
+                    }
+                }
+            }
+            found
+        }};
+    }
+
+    /// Run each scan over the `input`, comparing against the positions found by SSE2 and the
+    /// fallback
+    fn scans_match_narrower(input: &[u8]) -> bool {
+        use crate::{any, eq, range, not};
+        let cond = any!(eq(b'a'), range!(b'0'..=b'9'));
+        let expected = narrow_matches!(x86_64, input);
+        #[cfg(not(feature = "ensure-simd"))]
+        if narrow_matches!(fallback, input) != expected { return false; }
+
+        let first_unmet = (0..input.len()).find(|pos| !expected.contains(pos));
+        let mut valid = true;
+        if input.len() >= WIDTH {
+            // the full vector scan on its own, without the short input paths
+            unsafe { crate::arch::scan::for_all_ensure_ct(input, cond, &mut valid) };
+        } else {
+            valid = first_unmet.is_none();
+        }
+
+        crate::search_all(input, cond).eq(expected.iter().copied())
+            && crate::search(input, cond) == expected.first().copied()
+            && crate::rsearch(input, cond) == expected.last().copied()
+            && crate::count(input, cond) == expected.len()
+            && crate::for_all_ensure(input, cond) == first_unmet.is_none()
+            && crate::for_all_ensure_ct(input, cond) == first_unmet.is_none()
+            && crate::validate(input, cond).err().map(|invalid| invalid.offset()) == first_unmet
+            && crate::validate(input, not(cond)).err().map(|invalid| invalid.offset())
+                == expected.first().copied()
+            && crate::span(input, not(cond)) == expected.first().copied().unwrap_or(input.len())
+            && valid == first_unmet.is_none()
+    }
+
+    #[test]
+    fn scans_match_narrower_across_lengths() {
+        // mostly bytes not meeting the condition, so the short inputs often meet it nowhere and
+        // the long inputs cross vectors between matches
+        let data: Vec<u8> = (0..160u32)
+            .map(|i| b"a7xyz xyz-xyz.xyz_XYZ~"[(i.wrapping_mul(2654435761) >> 11) as usize % 22])
+            .collect();
+        // an input meeting the condition everywhere reaches the end of each scan
+        let met: Vec<u8> = (0..160u32).map(|i| b"a0123456789"[i as usize % 11]).collect();
+        for input in [&data, &met] {
+            // every alignment, and every length from empty across several vectors
+            for offset in 0..WIDTH {
+                for len in 0..=input.len() - offset {
+                    assert!(
+                        scans_match_narrower(&input[offset..offset + len]),
+                        "offset {offset}, length {len}"
+                    );
+                }
+            }
+        }
+    }
+
+    quickcheck! {
+        fn scans_match_narrower_random(s: Vec<u8>, offset: u8) -> bool {
+            let input = &s[(offset as usize % WIDTH).min(s.len())..];
+            scans_match_narrower(input)
+        }
+    }
+
+    #[test]
+    fn load_repeats_lanes() {
+        let input = b"aaaaaaaaaaaaaaaB";
+        assert_eq!(crate::find(load(input), crate::eq(b'B')), Some(15));
+        assert!(crate::ensure!(load(input), crate::range!(b'A'..=b'z')));
+        let partial = bytes(unsafe { load_partial(b"abc", 3) });
+        assert_eq!(&partial[..4], b"abc\0");
+        assert!(partial[3..].iter().all(|byte| *byte == 0));
+    }
+}
@@ -11,7 +11,43 @@ macro_rules! cfg_neon {
 macro_rules! cfg_sse {
     ($($item:item)*) => {
         $(
-            #[cfg(all(feature = "simd", target_arch = "x86_64", target_feature = "sse2"))]
+            #[cfg(all(
+                feature = "simd", target_arch = "x86_64", target_feature = "sse2",
+                not(target_feature = "avx2")
+            ))]
+            $item
+        )*
+    };
+}
+
+macro_rules! cfg_avx2 {
+    ($($item:item)*) => {
+        $(
+            #[cfg(all(feature = "simd", target_arch = "x86_64", target_feature = "avx2"))]
+            $item
+        )*
+    };
+}
+
+macro_rules! cfg_dispatch {
+    ($($item:item)*) => {
+        $(
+            #[cfg(all(
+                feature = "std", feature = "simd", target_arch = "x86_64", target_feature = "sse2",
+                not(target_feature = "avx2")
+            ))]
+            $item
+        )*
+    };
+}
+
+macro_rules! cfg_static {
+    ($($item:item)*) => {
+        $(
+            #[cfg(not(all(
+                feature = "std", feature = "simd", target_arch = "x86_64", target_feature = "sse2",
+                not(target_feature = "avx2")
+            )))]
             $item
         )*
     };
@@ -92,4 +128,20 @@ macro_rules! cfg_runtime {
             $item
         )*
     };
-}
\ No newline at end of file
+}
+/// Implement `Condition::eval_avx2`, only present where AVX2 is selected at runtime, with the
+/// `avx2` operations in scope
+macro_rules! eval_avx2 {
+    (|$self:ident, $data:ident| $body:expr) => {
+        #[cfg(all(
+            feature = "std", feature = "simd", target_arch = "x86_64", target_feature = "sse2",
+            not(target_feature = "avx2")
+        ))]
+        #[inline(always)]
+        unsafe fn eval_avx2(&$self, $data: crate::arch::avx2::Vector) -> crate::arch::avx2::Vector {
+            #[allow(unused_imports)]
+            use crate::arch::avx2;
+            $body
+        }
+    };
+}
@@ -1,6 +1,7 @@
 #![allow(clippy::missing_safety_doc)]
 
 const BYTE_MASK: u128 = 0x00FF;
+pub const WIDTH: usize = 16;
 pub type Vector = u128;
 pub type Ptr = [u8; 16];
 pub const STEP: usize = 16;
@@ -57,6 +58,31 @@ impl MoveMask {
     pub const fn trailing_ones(&self) -> u32 {
         self.0.trailing_ones()
     }
+    /// The number of set lanes
+    #[inline(always)] #[must_use]
+    pub const fn count_ones(&self) -> u32 {
+        self.0.count_ones()
+    }
+    /// The number of unset lanes preceding the highest set lane
+    #[inline(always)] #[must_use]
+    pub const fn leading_zeros(&self) -> u32 {
+        self.0.leading_zeros()
+    }
+    /// Unset the lowest lane which is set
+    #[inline(always)] #[must_use]
+    pub const fn clear_lowest(&self) -> Self {
+        Self(self.0 & self.0.wrapping_sub(1))
+    }
+    /// Unset every lane below `lane`
+    #[inline(always)] #[must_use]
+    pub const fn lanes_from(&self, lane: u32) -> Self {
+        Self(self.0 & (u32::MAX << lane) as u16)
+    }
+    /// Unset every lane at or above `lane`
+    #[inline(always)] #[must_use]
+    pub const fn lanes_below(&self, lane: u32) -> Self {
+        Self(self.0 & !(u32::MAX << lane) as u16)
+    }
 }
 
 impl_bit_ops!(MoveMask);
@@ -136,6 +162,50 @@ pub const fn splat(a: u8) -> Vector {
     result
 }
 
+#[inline] #[must_use]
+pub const fn sub(a: Vector, b: Vector) -> Vector {
+    let mut result = 0;
+    for_each_byte!(shift, |a, b| {
+        result |= ((a + 256 - b) & BYTE_MASK) << shift;
+    });
+    result
+}
+
+#[inline] #[must_use]
+pub const fn saturating_sub(a: Vector, b: Vector) -> Vector {
+    let mut result = 0;
+    for_each_byte!(shift, |a, b| {
+        result |= a.saturating_sub(b) << shift;
+    });
+    result
+}
+
+#[inline(always)] #[must_use]
+pub const fn high_nibbles(a: Vector) -> Vector {
+    (a >> 4) & splat(0x0F)
+}
+
+/// Select the byte of the `table` at each index of `idx`, each index must be below 16
+#[inline] #[must_use]
+pub const fn shuffle(table: Vector, idx: Vector) -> Vector {
+    let (table, idx) = (table.to_le_bytes(), idx.to_le_bytes());
+    let mut res = [0u8; 16];
+    let mut lane = 0;
+    while lane < 16 {
+        res[lane] = table[(idx[lane] & 0x0F) as usize];
+        lane += 1;
+    }
+    u128::from_le_bytes(res)
+}
+
+/// Shift each byte of `data` up `N` lanes, filling the lowest `N` lanes with the highest `N` lanes
+/// of `prev`, `N` must be within `1..16`
+#[inline(always)] #[must_use]
+pub const fn shift_in<const N: usize>(data: Vector, prev: Vector) -> Vector {
+    // the first lane is the least significant byte
+    (data << (N * 8)) | (prev >> (128 - N * 8))
+}
+
 #[inline(always)] #[must_use]
 pub const unsafe fn load_unchecked(data: &[u8]) -> Vector {
     u128::from_le_bytes(*data.as_ptr().cast())
@@ -152,7 +222,7 @@ pub const unsafe fn load_aligned(data: &[u8]) -> Vector {
 }
 
 #[inline(always)] #[must_use]
-pub unsafe fn maybe_aligned_load(data: &[u8]) -> crate::arch::Vector {
+pub unsafe fn maybe_aligned_load(data: &[u8]) -> Vector {
     load_unchecked(data)
 }
 
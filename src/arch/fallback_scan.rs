@@ -1,4 +1,6 @@
-use super::Vector;
+use super::MoveMask;
+use crate::condition::{eval_in_context, Context};
+use crate::Condition;
 
 macro_rules! scan_all {
     (
@@ -22,39 +24,189 @@ macro_rules! scan_all {
     }};
 }
 
+macro_rules! rscan_all {
+    (
+        $data:ident, $idx:ident,
+        |$chunk:ident| => $do:expr,
+        |$partial:ident| => $handle_partial:expr; or $or:expr
+    ) => {{
+        while $idx >= super::WIDTH {
+            $idx -= super::WIDTH;
+            let $chunk = &$data[$idx..$idx + super::WIDTH];
+            $do;
+        }
+        match $idx {
+            0 => $or,
+            _ => {
+                let $partial = &$data[..super::WIDTH];
+                $handle_partial
+            }
+        }
+    }};
+}
+
+/// Evaluate the `cond` on the `chunk` at `idx` within the `data`, the bytes preceding the `chunk`
+/// are only loaded for conditions which look behind
 #[inline(always)]
-pub unsafe fn for_all_ensure_ct(data: &[u8], cond: impl Fn(Vector) -> Vector, res: &mut bool) {
+unsafe fn eval(cond: &impl Condition, data: &[u8], chunk: &[u8], idx: usize) -> MoveMask {
+    MoveMask::new(eval_in_context(cond, super::load_unchecked(chunk), || Context::at(data, idx)))
+}
+
+#[inline(always)]
+fn first_set(mask: MoveMask) -> Option<usize> {
+    match mask.trailing_zeros() as usize {
+        len if len < super::WIDTH => Some(len),
+        _ => None
+    }
+}
+
+#[inline(always)]
+fn first_unset(mask: MoveMask) -> Option<usize> {
+    match mask.trailing_ones() as usize {
+        len if len < super::WIDTH => Some(len),
+        _ => None
+    }
+}
+
+#[inline(always)]
+fn rfind(mask: MoveMask) -> Option<usize> {
+    match mask.leading_zeros() as usize {
+        len if len < super::WIDTH => Some(super::WIDTH - 1 - len),
+        _ => None
+    }
+}
+
+#[inline(always)]
+pub unsafe fn for_all_ensure_ct(data: &[u8], cond: impl Condition, res: &mut bool) {
     let mut idx = 0;
     scan_all!(
         data, idx,
-        |chunk| => *res &= crate::ensure!(super::load_unchecked(chunk), cond),
-        |partial| => *res &= crate::ensure!(super::load_unchecked(partial), cond); or {}
+        |chunk| => *res &= eval(&cond, data, chunk, idx).all_bits_set(),
+        |partial| => *res &= eval(&cond, data, partial, idx).all_bits_set(); or {}
     );
 }
 
 #[inline(always)]
-pub unsafe fn for_all_ensure(data: &[u8], cond: impl Fn(Vector) -> Vector) -> bool {
+pub unsafe fn for_all_ensure(data: &[u8], cond: impl Condition) -> bool {
     let mut idx = 0;
     scan_all!(
         data, idx,
-        |chunk| => if !crate::ensure!(super::load_unchecked(chunk), cond) { return false },
-        |partial| => crate::ensure!(super::load_unchecked(partial), cond); or true
+        |chunk| => if !eval(&cond, data, chunk, idx).all_bits_set() { return false },
+        |partial| => eval(&cond, data, partial, idx).all_bits_set(); or true
     )
 }
 
 #[inline(always)]
-pub unsafe fn search(data: &[u8], cond: impl Fn(Vector) -> Vector) -> Option<usize> {
+pub unsafe fn search(data: &[u8], cond: impl Condition) -> Option<usize> {
     let mut idx = 0;
     scan_all!(
         data, idx,
-        |chunk| => if let Some(position) = crate::find!(super::load_unchecked(chunk), cond) {
-            return Some(position as usize + idx)
+        |chunk| => if let Some(position) = first_set(eval(&cond, data, chunk, idx)) {
+            return Some(position + idx)
         },
-        |partial| => crate::find!(super::load_unchecked(partial), cond)
-            .map(|pos| pos as usize + idx); or None
+        |partial| => first_set(eval(&cond, data, partial, idx))
+            .map(|pos| pos + idx); or None
     )
 }
 
+#[inline(always)]
+pub unsafe fn first_invalid(data: &[u8], cond: impl Condition) -> Option<usize> {
+    let mut idx = 0;
+    scan_all!(
+        data, idx,
+        |chunk| => if let Some(position) = first_unset(eval(&cond, data, chunk, idx)) {
+            return Some(position + idx)
+        },
+        |partial| => first_unset(eval(&cond, data, partial, idx))
+            .map(|pos| pos + idx); or None
+    )
+}
+
+#[inline(always)]
+pub unsafe fn rsearch(data: &[u8], cond: impl Condition) -> Option<usize> {
+    let mut idx = data.len();
+    rscan_all!(
+        data, idx,
+        |chunk| => if let Some(position) = rfind(eval(&cond, data, chunk, idx)) {
+            return Some(position + idx)
+        },
+        |partial| => rfind(eval(&cond, data, partial, 0)); or None
+    )
+}
+
+#[inline(always)] #[must_use]
+pub unsafe fn count(data: &[u8], cond: impl Condition) -> usize {
+    let mut idx = 0;
+    let mut total = 0;
+    scan_all!(
+        data, idx,
+        |chunk| => total += eval(&cond, data, chunk, idx).count_ones() as usize,
+        // the partial chunk overlaps with the previous chunk, skip what was already counted
+        |partial| => total + eval(&cond, data, partial, idx)
+            .lanes_from((super::WIDTH - data.len() % super::WIDTH) as u32)
+            .count_ones() as usize; or total
+    )
+}
+
+pub struct Matches<'a, F> {
+    cond: F,
+    data: &'a [u8],
+    idx: usize,
+    mask: super::MoveMask,
+    base: usize,
+    done: bool,
+}
+
+impl<'a, F: Condition> Matches<'a, F> {
+    #[inline(always)] #[must_use]
+    pub unsafe fn new(data: &'a [u8], cond: F) -> Self {
+        let mask = eval(&cond, data, &data[..super::WIDTH], 0);
+        Self { cond, data, idx: super::WIDTH, mask, base: 0, done: false }
+    }
+
+    #[inline(always)] #[must_use]
+    pub unsafe fn partial(data: &'a [u8], cond: F) -> Self {
+        let mask = super::MoveMask::new(cond.eval_vector(super::load_partial(data, data.len())))
+            .lanes_below(data.len() as u32);
+        Self { cond, data, idx: data.len(), mask, base: 0, done: true }
+    }
+}
+
+impl<'a, F: Condition> Iterator for Matches<'a, F> {
+    type Item = usize;
+
+    #[inline]
+    fn next(&mut self) -> Option<usize> {
+        loop {
+            if self.mask.any_bit_set() {
+                let lane = self.mask.trailing_zeros();
+                self.mask = self.mask.clear_lowest();
+                return Some(self.base + lane as usize);
+            }
+            if self.done { return None; }
+
+            if self.idx + super::WIDTH <= self.data.len() {
+                self.base = self.idx;
+                self.idx += super::WIDTH;
+                self.mask = unsafe {
+                    eval(&self.cond, self.data, &self.data[self.base..self.idx], self.base)
+                };
+            } else {
+                self.done = true;
+                match self.data.len() - self.idx {
+                    0 => return None,
+                    offset => {
+                        self.base = self.data.len() - super::WIDTH;
+                        self.mask = unsafe {
+                            eval(&self.cond, self.data, &self.data[self.base..], self.base)
+                        }.lanes_from((super::WIDTH - offset) as u32);
+                    }
+                }
+            }
+        }
+    }
+}
+
 #[cfg(feature = "require")]
 #[inline(always)]
 pub unsafe fn ensure_requirements<R: crate::require::Requirement>(data: &[u8], mut req: R) -> R {
@@ -1,4 +1,3 @@
-pub const WIDTH: usize = 16;
 #[macro_use]
 mod cfg_macros;
 
@@ -62,6 +61,25 @@ cfg_sse!(
     pub use x86_64 as arch;
 );
 
+cfg_dispatch!(
+    // selected at runtime, within the scans of `wide` compiled with AVX2 enabled
+    #[doc(hidden)]
+    pub mod avx2;
+    #[doc(hidden)]
+    pub mod wide;
+);
+
+cfg_avx2!(
+    pub mod avx2;
+    pub use avx2 as arch;
+
+    // the narrower backends are kept around to compare against
+    #[cfg(test)]
+    pub mod x86_64;
+    #[cfg(all(test, not(feature = "ensure-simd")))]
+    pub mod fallback;
+);
+
 cfg_simd128!(
     pub mod wasm;
     pub use wasm as arch;
@@ -74,16 +92,58 @@ cfg_fallback!(
 
 #[doc(hidden)]
 pub use arch::{
-    eq, not, xor, or, and, splat, byte_ptr, simd_ptr, load_partial, load_aligned, maybe_aligned_load
+    eq, not, xor, or, and, splat, byte_ptr, simd_ptr, load_partial, load_aligned, maybe_aligned_load,
+    shuffle, high_nibbles, sub, saturating_sub, shift_in
 };
 
 #[doc(hidden)]
 pub use arch::{MoveMask, Ptr, STEP, STEP_SIZE};
 
+/// The number of bytes in a `Vector` for the active backend
+pub use arch::WIDTH;
+
+/// If [`shuffle`] is a single instruction, SSE2 without SSSE3 and the fallback emulate it with far
+/// more
+#[doc(hidden)]
+pub const NATIVE_SHUFFLE: bool = cfg!(all(feature = "simd", any(
+    all(target_arch = "x86_64", target_feature = "ssse3"),
+    all(target_arch = "aarch64", target_feature = "neon"),
+    all(target_family = "wasm", target_feature = "simd128")
+)));
+
 pub use arch::Vector;
 pub use arch::{load, load_unchecked};
 
 cfg_simd!(
+    /// Load the vector starting at `offset`, which need not be aligned
+    ///
+    /// # Safety
+    ///
+    /// `offset + WIDTH` must not exceed the length of the `data`
+    #[inline(always)]
+    pub(crate) unsafe fn load_at(data: &[u8], offset: usize) -> Vector {
+        load_unchecked(simd_ptr(data.as_ptr().add(offset)))
+    }
+);
+
+cfg_fallback!(
+    /// Load the vector starting at `offset`, see the SIMD `load_at`
+    #[inline(always)]
+    pub(crate) unsafe fn load_at(data: &[u8], offset: usize) -> Vector {
+        load_unchecked(data.get_unchecked(offset..))
+    }
+);
+
+cfg_simd!(
+    // the scans take these from their parent module, `wide` provides its own for AVX2
+    use crate::condition::{eval_in_context, Context};
+
+    #[cfg(feature = "require")]
+    #[inline(always)]
+    fn check_requirements<R: crate::require::Requirement>(req: &mut R, data: Vector) {
+        req.check(data);
+    }
+
     #[doc(hidden)]
     pub mod simd_scan;
     #[doc(hidden)]
@@ -146,7 +206,7 @@ cfg_i8!(
     }
 
     #[doc(hidden)] #[inline(always)]
-    pub const fn greater_than<const MIN: u8>() -> impl Fn(Vector) -> Vector {
+    pub const fn greater_than<const MIN: u8>() -> impl Fn(Vector) -> Vector + Copy {
         unsafe { impl_gt!(
             MIN,
             greater_than,
@@ -155,7 +215,7 @@ cfg_i8!(
     }
 
     #[doc(hidden)] #[inline(always)]
-    pub const fn greater_than_or_eq<const MIN: u8>() -> impl Fn(Vector) -> Vector {
+    pub const fn greater_than_or_eq<const MIN: u8>() -> impl Fn(Vector) -> Vector + Copy {
         unsafe { impl_gt!(
             MIN,
             greater_than_or_eq,
@@ -193,7 +253,7 @@ cfg_i8!(
     }
 
     #[doc(hidden)] #[inline(always)]
-    pub const fn less_than<const MAX: u8>() -> impl Fn(Vector) -> Vector {
+    pub const fn less_than<const MAX: u8>() -> impl Fn(Vector) -> Vector + Copy {
         impl_lt!(
             MAX,
             less_than,
@@ -205,7 +265,7 @@ cfg_i8!(
     }
 
     #[doc(hidden)] #[inline(always)]
-    pub const fn less_than_or_eq<const MAX: u8>() -> impl Fn(Vector) -> Vector {
+    pub const fn less_than_or_eq<const MAX: u8>() -> impl Fn(Vector) -> Vector + Copy {
         impl_lt!(
             MAX,
             less_than_or_eq,
@@ -248,7 +308,7 @@ cfg_i8!(
     }
 
     #[doc(hidden)] #[inline(always)]
-    pub const fn range<const MIN: u8, const MAX: u8>() -> impl Fn(Vector) -> Vector {
+    pub const fn range<const MIN: u8, const MAX: u8>() -> impl Fn(Vector) -> Vector + Copy {
         impl_range_cast!(MIN, MAX, greater_than_or_eq, less_than_or_eq,
             move |data| unsafe { eq(data, splat(MIN)) },
             move |data| unsafe {
@@ -261,7 +321,7 @@ cfg_i8!(
     }
 
     #[doc(hidden)] #[inline(always)]
-    pub const fn exclusive_range<const MIN: u8, const MAX: u8>() -> impl Fn(Vector) -> Vector {
+    pub const fn exclusive_range<const MIN: u8, const MAX: u8>() -> impl Fn(Vector) -> Vector + Copy {
         if MIN.abs_diff(MAX) == 1 {
             move |_data: Vector| -> Vector { unsafe { splat(0) } }
         } else {
@@ -275,7 +335,7 @@ cfg_i8!(
 
 cfg_u8!(
     #[doc(hidden)] #[inline(always)]
-    pub const fn less_than<const MAX: u8>() -> impl Fn(Vector) -> Vector {
+    pub const fn less_than<const MAX: u8>() -> impl Fn(Vector) -> Vector + Copy {
         match MAX {
             0 => move |_| unsafe {
                 // always false
@@ -288,7 +348,7 @@ cfg_u8!(
     }
 
     #[doc(hidden)] #[inline(always)]
-    pub const fn less_than_or_eq<const MAX: u8>() -> impl Fn(Vector) -> Vector {
+    pub const fn less_than_or_eq<const MAX: u8>() -> impl Fn(Vector) -> Vector + Copy {
         match MAX {
             255 => move |_| unsafe {
                 // always true
@@ -301,7 +361,7 @@ cfg_u8!(
     }
 
     #[doc(hidden)] #[inline(always)]
-    pub const fn greater_than<const MIN: u8>() -> impl Fn(Vector) -> Vector {
+    pub const fn greater_than<const MIN: u8>() -> impl Fn(Vector) -> Vector + Copy {
         match MIN {
             255 => move |_| unsafe {
                 // always false
@@ -314,7 +374,7 @@ cfg_u8!(
     }
 
     #[doc(hidden)] #[inline(always)]
-    pub const fn greater_than_or_eq<const MIN: u8>() -> impl Fn(Vector) -> Vector {
+    pub const fn greater_than_or_eq<const MIN: u8>() -> impl Fn(Vector) -> Vector + Copy {
         match MIN {
             0 => move |_| unsafe {
                 // always true
@@ -327,7 +387,7 @@ cfg_u8!(
     }
 
     #[doc(hidden)] #[inline(always)]
-    pub const fn range<const MIN: u8, const MAX: u8>() -> impl Fn(Vector) -> Vector {
+    pub const fn range<const MIN: u8, const MAX: u8>() -> impl Fn(Vector) -> Vector + Copy {
         match (MIN, MAX) {
             _ if MIN == MAX => move |data| unsafe { eq(data, splat(MIN)) },
             _ => move |data| unsafe {
@@ -340,7 +400,7 @@ cfg_u8!(
     }
 
     #[doc(hidden)] #[inline(always)]
-    pub const fn exclusive_range<const MIN: u8, const MAX: u8>() -> impl Fn(Vector) -> Vector {
+    pub const fn exclusive_range<const MIN: u8, const MAX: u8>() -> impl Fn(Vector) -> Vector + Copy {
         match (MIN, MAX, MIN.abs_diff(MAX)) {
             (_, _, 1) |
             (_, _, _) if MIN == MAX => move |_| unsafe { splat(0) },
@@ -1,16 +1,20 @@
 #![allow(clippy::let_and_return)] // the contracts require this and without the `verify` feature
                                   // these bindings will cause warnings.
 
-use crate::arch::{self, byte_ptr, simd_ptr, Vector};
+// the backend comes from the parent module, rather than `crate::arch`, so the scans can also be
+// instantiated for AVX2 where it is only selected at runtime, see `arch::wide`
+use super::arch::{self, byte_ptr, simd_ptr, Vector};
+use super::{eval_in_context, Context};
+use crate::Condition;
 
 cfg_verify!(
-    use crate::arch::is_aligned;
+    use super::is_aligned;
     use mirai_annotations::{checked_precondition, checked_postcondition};
 );
 
 mod end_ptr {
     cfg_verify!(use super::checked_postcondition;);
-    use crate::arch::Ptr;
+    use super::arch::Ptr;
 
     /// An immutable representation of the `data`'s upper bound
     #[derive(Copy, Clone)]
@@ -42,6 +46,47 @@ mod end_ptr {
     }
 }
 
+mod start_ptr {
+    cfg_verify!(use super::checked_postcondition;);
+    use super::arch::Ptr;
+
+    /// An immutable representation of the `data`'s lower bound
+    #[derive(Copy, Clone)]
+    #[repr(transparent)]
+    pub struct StartPtr(*const Ptr);
+
+    impl StartPtr {
+        #[inline(always)] #[must_use]
+        pub const fn new(data: &[u8]) -> Self {
+            Self ( data.as_ptr().cast() )
+        }
+    }
+
+    impl StartPtr {
+        #[cfg_attr(feature = "verify", contracts::ensures(self.0 == ret))]
+        #[cfg_attr(feature = "verify", contracts::ensures(self.0 == old(self.0)))]
+        #[inline(always)] #[must_use]
+        pub fn get(&self) -> *const Ptr {
+            self.0
+        }
+
+        /// Checks that the underlying pointer has not changed, see `EndPtr::check`.
+        #[cfg(feature = "verify")]
+        pub fn check(&self, data: &[u8]) -> bool {
+            super::byte_ptr(self.get()) == super::byte_ptr(Self::new(data).get())
+        }
+    }
+}
+
+macro_rules! check_start_ptr {
+    ($start_ptr:expr, $data:expr) => {
+        #[cfg(feature = "verify")]
+        assert!($start_ptr.check($data))
+    };
+}
+
+use start_ptr::StartPtr;
+
 macro_rules! check_end_ptr {
     ($end_ptr:expr, $data:expr) => {
         #[cfg(feature = "verify")]
@@ -130,6 +175,40 @@ unsafe fn align_ptr_or_incr(ptr: *const u8) -> (Vector, *const arch::Ptr) {
     }
 }
 
+#[cfg_attr(feature = "verify", contracts::ensures(
+    end.align_offset(arch::WIDTH) == 0 -> ret.1 == decr_ptr(simd_ptr(end))
+))]
+#[cfg_attr(feature = "verify", contracts::ensures(
+    end.align_offset(arch::WIDTH) != 0
+        -> end.offset_from(ret.1.cast::<u8>()) as usize
+            == arch::WIDTH - end.align_offset(arch::WIDTH)
+))]
+#[inline(always)] #[must_use]
+unsafe fn align_ptr_or_decr(end: *const u8) -> (Vector, *const arch::Ptr) {
+    let last = simd_ptr(end.sub(arch::WIDTH));
+    match end.align_offset(arch::WIDTH) {
+        // When the end is already aligned, the final vector is the first aligned vector, so
+        // decrement past it
+        0 => (arch::load_aligned(last), last),
+        // When the end is not aligned, adjust it back to the previous alignment boundary
+        offset => (arch::load_unchecked(last), simd_ptr(end.sub(arch::WIDTH - offset)))
+    }
+}
+
+/// The number of leading bytes the first `Vector` of `init_scan` covers which the `AlignedIter`
+/// does not
+#[cfg_attr(feature = "verify", contracts::ensures(ret > 0 && ret <= arch::WIDTH))]
+#[cfg_attr(feature = "verify", contracts::ensures(
+    ptr.align_offset(arch::WIDTH) != 0 -> ret == ptr.align_offset(arch::WIDTH)
+))]
+#[inline(always)] #[must_use]
+fn prologue_len(ptr: *const u8) -> usize {
+    match ptr.align_offset(arch::WIDTH) {
+        0 => arch::WIDTH,
+        offset => offset
+    }
+}
+
 #[cfg_attr(feature = "verify", contracts::requires(end >= cur))]
 #[cfg_attr(feature = "verify", contracts::ensures(is_aligned(cur) -> is_aligned(cur)))]
 #[cfg_attr(feature = "verify", contracts::ensures(ret -> incr_ptr(cur) <= end))]
@@ -138,6 +217,15 @@ unsafe fn can_proceed(cur: *const arch::Ptr, end: *const arch::Ptr) -> bool {
     cur <= decr_ptr(end)
 }
 
+#[cfg_attr(feature = "verify", contracts::requires(cur >= start))]
+#[cfg_attr(feature = "verify", contracts::ensures(ret -> decr_ptr(cur) >= start))]
+#[inline(always)] #[must_use]
+unsafe fn can_recede(cur: *const arch::Ptr, start: *const arch::Ptr) -> bool {
+    // `decr_ptr` is not used for the comparison as `cur` may be less than `arch::WIDTH` from
+    // `start`, in which case decrementing it would leave the bounds of `data`.
+    simd_distance(cur, start) >= arch::WIDTH
+}
+
 mod sealed {
     use super::*;
     cfg_verify!(use contracts::invariant;);
@@ -157,7 +245,7 @@ mod sealed {
         let (vector, aligned_ptr) = align_ptr_or_incr(data.as_ptr());
         (
             vector,
-            AlignedIter::after_first(aligned_ptr, data)
+            AlignedIter::after_first(aligned_ptr, vector, data)
         )
     }
 
@@ -165,6 +253,37 @@ mod sealed {
         cur: *const arch::Ptr,
         // `EndPtr` cannot be mutated so is it safe to expose.
         pub end: EndPtr,
+        /// The `Vector` most recently loaded, preceding `cur`
+        last: Vector,
+        /// The `Vector` preceding the one most recently yielded, carried over for conditions which
+        /// look behind
+        behind: Vector,
+    }
+
+    /// Initiate the reverse scanning process
+    ///
+    /// # Returns
+    ///
+    /// 0. The final `Vector` associated with `data` and the pointer it was loaded from. This most
+    ///    likely overlaps with the first `Vector` yielded by the `RevAlignedIter`, so it must be
+    ///    operated on independently.
+    /// 1. The `RevAlignedIter` which will handle loading all data preceding the final `Vector`
+    ///    (`0`).
+    #[cfg_attr(feature = "verify", contracts::requires(data.len() >= arch::WIDTH))]
+    #[inline(always)] #[must_use]
+    pub unsafe fn init_rscan(data: &[u8]) -> (Remainder, RevAlignedIter) {
+        let end = byte_ptr(EndPtr::new(data).get());
+        let (vector, aligned_ptr) = align_ptr_or_decr(end);
+        (
+            (vector, simd_ptr(end.sub(arch::WIDTH))),
+            RevAlignedIter::before_last(aligned_ptr, data)
+        )
+    }
+
+    pub struct RevAlignedIter {
+        cur: *const arch::Ptr,
+        // `StartPtr` cannot be mutated so is it safe to expose.
+        pub start: StartPtr,
     }
 
     pub type Remainder = (Vector, *const arch::Ptr);
@@ -203,8 +322,21 @@ mod sealed {
             "To create an `AlignedIter` the `cur` pointer must be aligned to the `arch::WIDTH`"
         ))]
         #[inline(always)] #[must_use]
-        unsafe fn after_first(aligned_ptr: *const arch::Ptr, data: &[u8]) -> Self {
-            Self { cur: aligned_ptr, end: EndPtr::new(data) }
+        unsafe fn after_first(aligned_ptr: *const arch::Ptr, first: Vector, data: &[u8]) -> Self {
+            Self { cur: aligned_ptr, end: EndPtr::new(data), last: first, behind: first }
+        }
+
+        /// The `Context` of the `Vector` most recently yielded from `ptr` by [`AlignedIter::next`]
+        ///
+        /// Each aligned `Vector` is preceded by the one yielded before it, which is carried over
+        /// rather than loaded again. The first aligned `Vector` is preceded by the `Vector` of
+        /// `init_scan` when `data` is aligned, otherwise the two overlap.
+        #[inline(always)] #[must_use]
+        pub unsafe fn context(&self, ptr: *const arch::Ptr, data: &[u8]) -> Context {
+            match distance(byte_ptr(ptr), data.as_ptr()) {
+                offset if offset < arch::WIDTH => Context::at(data, offset),
+                _ => Context::after(self.behind)
+            }
         }
 
         #[cfg_attr(feature = "verify", contracts::ensures(is_aligned(ret)))]
@@ -238,7 +370,9 @@ mod sealed {
             if can_proceed(self.cur, self.end.get()) {
                 Pointer::Aligned({
                     let ptr = self.snap_and_incr();
-                    (arch::load_aligned(ptr), ptr)
+                    let vector = arch::load_aligned(ptr);
+                    self.behind = core::mem::replace(&mut self.last, vector);
+                    (vector, ptr)
                 })
             } else {
                 // As `can_proceed` failed and our invariant requires `end` to be greater than
@@ -294,6 +428,64 @@ mod sealed {
             }
         }
     }
+
+    #[cfg_attr(feature = "verify", invariant(self.cur >= self.start.get()))]
+    impl RevAlignedIter {
+        #[cfg_attr(feature = "verify", contracts::requires(
+            is_aligned(aligned_ptr),
+            "To create a `RevAlignedIter` the `cur` pointer must be aligned to the `arch::WIDTH`"
+        ))]
+        #[inline(always)] #[must_use]
+        unsafe fn before_last(aligned_ptr: *const arch::Ptr, data: &[u8]) -> Self {
+            Self { cur: aligned_ptr, start: StartPtr::new(data) }
+        }
+
+        #[cfg_attr(feature = "verify", contracts::ensures(
+            ret.is_aligned() -> is_aligned(self.cur)
+        ))]
+        #[cfg_attr(feature = "verify", contracts::ensures(
+            ret.is_end_with_remaining() -> ret.remaining_end_ptr() == self.start.get()
+        ))]
+        #[inline(always)] #[must_use]
+        pub unsafe fn next(&mut self) -> Pointer {
+            if can_recede(self.cur, self.start.get()) {
+                Pointer::Aligned({
+                    let ptr = decr_ptr(self.cur);
+                    self.cur = ptr;
+                    (arch::load_aligned(ptr), ptr)
+                })
+            } else {
+                // As `can_recede` failed and our invariant requires `cur` to be greater than
+                // `start` we know the distance between them is less than `arch::WIDTH`
+                Pointer::End(self.start())
+            }
+        }
+
+        /// Handle the potential unaligned leading bytes of `data`
+        ///
+        /// This mirrors [`AlignedIter::end`], so the same restrictions apply. As `data` is at
+        /// least `arch::WIDTH` long the first `arch::WIDTH` bytes can always be loaded, overlapping
+        /// with what the `RevAlignedIter` has already yielded.
+        ///
+        /// # Returns
+        ///
+        /// * `Some(Remainder)` - There were leading bytes which could not be loaded aligned, the
+        ///   pointer in `Remainder` is guaranteed to be the start of `data`.
+        /// * `None` - There was no remainder and the scan can be considered completed.
+        #[cfg_attr(feature = "verify", contracts::ensures(
+            ret.is_some() -> ret.unwrap().1 == self.start.get()
+        ))]
+        #[inline(always)]
+        unsafe fn start(&self) -> Option<Remainder> {
+            match simd_distance(self.cur, self.start.get()) {
+                0 => None,
+                _ => {
+                    let ptr = self.start.get();
+                    Some((arch::load_unchecked(ptr), ptr))
+                }
+            }
+        }
+    }
 }
 
 #[cfg_attr(feature = "verify", contracts::ensures(ret -> len < arch::WIDTH as u32))]
@@ -347,6 +539,52 @@ unsafe fn final_length(len: u32, cur: *const u8, data: &[u8], _end: EndPtr) -> u
     ret
 }
 
+/// Post-condition: As long as the preconditions are respected the returned value will always be
+/// less than `data.len()`
+#[cfg_attr(feature = "verify", contracts::requires(data.len() >= arch::WIDTH))]
+#[cfg_attr(feature = "verify", contracts::requires(
+    valid_len(len),
+    "The length must be below the SIMD register width, it being outside of this range denotes that \
+     find operation did not succeed."
+))]
+#[cfg_attr(feature = "verify", contracts::requires(
+    cur >= data.as_ptr(),
+    "The `cur` pointer must not have moved backwards beyond the start of `data`"
+))]
+#[cfg_attr(feature = "verify", contracts::requires(
+    incr_ptr(simd_ptr(cur)) <= _end.get(),
+    "The `Vector` loaded from `cur` must not have exceeded the data's upper bound"
+))]
+#[inline(always)] #[must_use]
+unsafe fn final_rlength(len: u32, cur: *const u8, data: &[u8], _end: EndPtr) -> usize {
+    let ret = distance(cur, data.as_ptr()).wrapping_add(arch::WIDTH - 1 - u32_as_usize(len));
+    // Relevant Preconditions:
+    //
+    // P(1) `incr_ptr(simd_ptr(cur)) <= _end.get()`: Guarantees that the distance between `cur`
+    //   and `data.as_ptr()` is at most the data's length subtracted by `arch::WIDTH`.
+    // P(2) `valid_len(len)`: Guarantees that `arch::WIDTH - 1 - len` is less than `arch::WIDTH`
+    //   and does not underflow.
+    //
+    // Therefore the sum is guaranteed less than the data's length.
+    contract!(assumed_postcondition!(ret < data.len()));
+    ret
+}
+
+/// Evaluate the `cond` on the `vector` into a `MoveMask`, where `ctx` is only evaluated for
+/// conditions which look behind
+macro_rules! eval {
+    ($cond:expr, $vector:expr, $ctx:expr) => {
+        arch::MoveMask::new(eval_in_context(&$cond, $vector, || $ctx))
+    };
+}
+
+/// The `Context` of the `Vector` loaded from `ptr`, for vectors not carried over by the iterators
+macro_rules! context_at {
+    ($data:expr, $ptr:expr) => {
+        Context::at($data, distance(byte_ptr($ptr), $data.as_ptr()))
+    };
+}
+
 macro_rules! valid_len_then {
     ($len:ident, $do:expr $(, $otherwise:expr)?) => {
         if valid_len($len) {
@@ -362,17 +600,17 @@ macro_rules! valid_len_then {
 #[cfg_attr(feature = "verify", contracts::requires(data.len() >= arch::WIDTH))]
 #[cfg_attr(feature = "verify", contracts::ensures(ret.is_some() -> ret.unwrap() < data.len()))]
 #[inline(always)]
-pub unsafe fn search<F: Fn(Vector) -> Vector>(data: &[u8], cond: F) -> Option<usize> {
+pub unsafe fn search<F: Condition>(data: &[u8], cond: F) -> Option<usize> {
     let (vector, mut iter) = sealed::init_scan(data);
 
-    let len = arch::MoveMask::new(cond(vector)).trailing_zeros();
+    let len = eval!(cond, vector, Context::start()).trailing_zeros();
     if valid_len(len) { return Some(len as usize); }
 
     loop {
         match iter.next() {
             sealed::Pointer::Aligned((vector, ptr)) => {
                 check_end_ptr!(iter.end, data);
-                let len = arch::MoveMask::new(cond(vector)).trailing_zeros();
+                let len = eval!(cond, vector, iter.context(ptr, data)).trailing_zeros();
                 valid_len_then!(
                     len,
                     break Some(final_length(len, byte_ptr(ptr), data, iter.end))
@@ -380,7 +618,7 @@ pub unsafe fn search<F: Fn(Vector) -> Vector>(data: &[u8], cond: F) -> Option<us
             },
             sealed::Pointer::End(Some((vector, ptr))) => {
                 check_end_ptr!(iter.end, data);
-                let len = arch::MoveMask::new(cond(vector)).trailing_zeros();
+                let len = eval!(cond, vector, context_at!(data, ptr)).trailing_zeros();
                 break valid_len_then!(
                     len,
                     Some(final_length(len, byte_ptr(ptr), data, iter.end)),
@@ -396,20 +634,205 @@ pub unsafe fn search<F: Fn(Vector) -> Vector>(data: &[u8], cond: F) -> Option<us
 }
 
 #[cfg_attr(feature = "verify", contracts::requires(data.len() >= arch::WIDTH))]
+#[cfg_attr(feature = "verify", contracts::ensures(ret.is_some() -> ret.unwrap() < data.len()))]
 #[inline(always)]
-pub unsafe fn for_all_ensure_ct<F: Fn(Vector) -> Vector>(data: &[u8], cond: F, res: &mut bool) {
+pub unsafe fn rsearch<F: Condition>(data: &[u8], cond: F) -> Option<usize> {
+    let ((vector, ptr), mut iter) = sealed::init_rscan(data);
+    let end = EndPtr::new(data);
+
+    let len = eval!(cond, vector, context_at!(data, ptr)).leading_zeros();
+    if valid_len(len) { return Some(final_rlength(len, byte_ptr(ptr), data, end)); }
+
+    loop {
+        match iter.next() {
+            sealed::Pointer::Aligned((vector, ptr)) => {
+                check_start_ptr!(iter.start, data);
+                let len = eval!(cond, vector, context_at!(data, ptr)).leading_zeros();
+                valid_len_then!(
+                    len,
+                    break Some(final_rlength(len, byte_ptr(ptr), data, end))
+                );
+            },
+            sealed::Pointer::End(Some((vector, ptr))) => {
+                check_start_ptr!(iter.start, data);
+                let len = eval!(cond, vector, context_at!(data, ptr)).leading_zeros();
+                break valid_len_then!(
+                    len,
+                    Some(final_rlength(len, byte_ptr(ptr), data, end)),
+                    None
+                );
+            },
+            sealed::Pointer::End(None) => {
+                check_start_ptr!(iter.start, data);
+                break None;
+            }
+        }
+    }
+}
+
+#[cfg_attr(feature = "verify", contracts::requires(data.len() >= arch::WIDTH))]
+#[cfg_attr(feature = "verify", contracts::ensures(ret <= data.len()))]
+#[inline(always)] #[must_use]
+pub unsafe fn count<F: Condition>(data: &[u8], cond: F) -> usize {
     let (vector, mut iter) = sealed::init_scan(data);
-    *res &= crate::ensure!(vector, cond);
+    // Unlike `search`, the overlapping lanes of the first and final `Vector` would be counted twice,
+    // so they must be masked out.
+    let mut scanned = prologue_len(data.as_ptr());
+    let mut total = u32_as_usize(
+        eval!(cond, vector, Context::start()).lanes_below(scanned as u32).count_ones()
+    );
 
     loop {
         match iter.next() {
-            sealed::Pointer::Aligned((vector, _)) => {
+            sealed::Pointer::Aligned((vector, ptr)) => {
                 check_end_ptr!(iter.end, data);
-                *res &= crate::ensure!(vector, cond);
+                total += u32_as_usize(eval!(cond, vector, iter.context(ptr, data)).count_ones());
+                scanned = distance(byte_ptr(ptr), data.as_ptr()) + arch::WIDTH;
             },
-            sealed::Pointer::End(Some((vector, _))) => {
+            sealed::Pointer::End(Some((vector, ptr))) => {
                 check_end_ptr!(iter.end, data);
-                *res &= crate::ensure!(vector, cond);
+                let overlap = scanned - distance(byte_ptr(ptr), data.as_ptr());
+                break total + u32_as_usize(
+                    eval!(cond, vector, context_at!(data, ptr)).lanes_from(overlap as u32).count_ones()
+                );
+            },
+            sealed::Pointer::End(None) => {
+                check_end_ptr!(iter.end, data);
+                break total;
+            }
+        }
+    }
+}
+
+/// Iterate over each position the condition was met in a single pass over `data`
+///
+/// Each `MoveMask` is drained lane by lane before the next `Vector` is loaded, so no part of the
+/// alignment prologue is repeated between matches.
+pub struct Matches<'a, F> {
+    cond: F,
+    data: &'a [u8],
+    iter: Option<sealed::AlignedIter>,
+    mask: arch::MoveMask,
+    /// The offset of the current `mask`'s first lane from the start of `data`
+    base: usize,
+    /// The offset from the start of `data` which every prior `Vector` has covered
+    scanned: usize,
+}
+
+impl<'a, F: Condition> Matches<'a, F> {
+    #[cfg_attr(feature = "verify", contracts::requires(data.len() >= arch::WIDTH))]
+    #[inline(always)] #[must_use]
+    pub unsafe fn new(data: &'a [u8], cond: F) -> Self {
+        let (vector, iter) = sealed::init_scan(data);
+        // The first `Vector` overlaps with the `AlignedIter` when `data` is not aligned, only the
+        // lanes preceding the first aligned pointer may be yielded to avoid duplicates.
+        let scanned = prologue_len(data.as_ptr());
+        let mask = eval!(cond, vector, Context::start()).lanes_below(scanned as u32);
+        Self { cond, data, iter: Some(iter), mask, base: 0, scanned }
+    }
+
+    /// Create `Matches` over data which is shorter than `arch::WIDTH`
+    #[cfg_attr(feature = "verify", contracts::requires(data.len() < arch::WIDTH))]
+    #[inline(always)] #[must_use]
+    pub unsafe fn partial(data: &'a [u8], cond: F) -> Self {
+        // the partial load pads with zeroes, these must never be yielded
+        let mask = eval!(cond, arch::load_partial(data, data.len()), Context::start())
+            .lanes_below(data.len() as u32);
+        Self { cond, data, iter: None, mask, base: 0, scanned: data.len() }
+    }
+}
+
+impl<'a, F: Condition> Iterator for Matches<'a, F> {
+    type Item = usize;
+
+    #[inline]
+    fn next(&mut self) -> Option<usize> {
+        loop {
+            if self.mask.any_bit_set() {
+                let lane = self.mask.trailing_zeros();
+                self.mask = self.mask.clear_lowest();
+                return Some(self.base + u32_as_usize(lane));
+            }
+
+            let iter = self.iter.as_mut()?;
+            unsafe { match iter.next() {
+                sealed::Pointer::Aligned((vector, ptr)) => {
+                    check_end_ptr!(iter.end, self.data);
+                    self.base = distance(byte_ptr(ptr), self.data.as_ptr());
+                    self.scanned = self.base + arch::WIDTH;
+                    self.mask = eval!(self.cond, vector, iter.context(ptr, self.data));
+                },
+                sealed::Pointer::End(Some((vector, ptr))) => {
+                    check_end_ptr!(iter.end, self.data);
+                    self.base = distance(byte_ptr(ptr), self.data.as_ptr());
+                    // the remainder is loaded `arch::WIDTH` back from the end, so it overlaps with
+                    // what has already been scanned.
+                    self.mask = eval!(self.cond, vector, context_at!(self.data, ptr))
+                        .lanes_from((self.scanned - self.base) as u32);
+                    self.iter = None;
+                },
+                sealed::Pointer::End(None) => {
+                    check_end_ptr!(iter.end, self.data);
+                    self.iter = None;
+                    return None;
+                }
+            }}
+        }
+    }
+}
+
+/// Find the first byte which did not meet the `cond`, via the trailing ones of the `MoveMask`
+#[cfg_attr(feature = "verify", contracts::requires(data.len() >= arch::WIDTH))]
+#[cfg_attr(feature = "verify", contracts::ensures(ret.is_some() -> ret.unwrap() < data.len()))]
+#[inline(always)]
+pub unsafe fn first_invalid<F: Condition>(data: &[u8], cond: F) -> Option<usize> {
+    let (vector, mut iter) = sealed::init_scan(data);
+
+    let len = eval!(cond, vector, Context::start()).trailing_ones();
+    if valid_len(len) { return Some(len as usize); }
+
+    loop {
+        match iter.next() {
+            sealed::Pointer::Aligned((vector, ptr)) => {
+                check_end_ptr!(iter.end, data);
+                let len = eval!(cond, vector, iter.context(ptr, data)).trailing_ones();
+                valid_len_then!(
+                    len,
+                    break Some(final_length(len, byte_ptr(ptr), data, iter.end))
+                );
+            },
+            sealed::Pointer::End(Some((vector, ptr))) => {
+                check_end_ptr!(iter.end, data);
+                let len = eval!(cond, vector, context_at!(data, ptr)).trailing_ones();
+                break valid_len_then!(
+                    len,
+                    Some(final_length(len, byte_ptr(ptr), data, iter.end)),
+                    None
+                );
+            },
+            sealed::Pointer::End(None) => {
+                check_end_ptr!(iter.end, data);
+                break None;
+            }
+        }
+    }
+}
+
+#[cfg_attr(feature = "verify", contracts::requires(data.len() >= arch::WIDTH))]
+#[inline(always)]
+pub unsafe fn for_all_ensure_ct<F: Condition>(data: &[u8], cond: F, res: &mut bool) {
+    let (vector, mut iter) = sealed::init_scan(data);
+    *res &= eval!(cond, vector, Context::start()).all_bits_set();
+
+    loop {
+        match iter.next() {
+            sealed::Pointer::Aligned((vector, ptr)) => {
+                check_end_ptr!(iter.end, data);
+                *res &= eval!(cond, vector, iter.context(ptr, data)).all_bits_set();
+            },
+            sealed::Pointer::End(Some((vector, ptr))) => {
+                check_end_ptr!(iter.end, data);
+                *res &= eval!(cond, vector, context_at!(data, ptr)).all_bits_set();
                 break;
             },
             sealed::Pointer::End(None) => {
@@ -422,19 +845,19 @@ pub unsafe fn for_all_ensure_ct<F: Fn(Vector) -> Vector>(data: &[u8], cond: F, r
 
 #[cfg_attr(feature = "verify", contracts::requires(data.len() >= arch::WIDTH))]
 #[inline(always)] #[must_use]
-pub unsafe fn for_all_ensure<F: Fn(Vector) -> Vector>(data: &[u8], cond: F) -> bool {
+pub unsafe fn for_all_ensure<F: Condition>(data: &[u8], cond: F) -> bool {
     let (vector, mut iter) = sealed::init_scan(data);
-    if !crate::ensure!(vector, cond) { return false; }
+    if !eval!(cond, vector, Context::start()).all_bits_set() { return false; }
 
     loop {
         match iter.next() {
-            sealed::Pointer::Aligned((vector, _)) => {
+            sealed::Pointer::Aligned((vector, ptr)) => {
                 check_end_ptr!(iter.end, data);
-                if !crate::ensure!(vector, cond) { break false; }
+                if !eval!(cond, vector, iter.context(ptr, data)).all_bits_set() { break false; }
             },
-            sealed::Pointer::End(Some((vector, _))) => {
+            sealed::Pointer::End(Some((vector, ptr))) => {
                 check_end_ptr!(iter.end, data);
-                break crate::ensure!(vector, cond);
+                break eval!(cond, vector, context_at!(data, ptr)).all_bits_set();
             },
             sealed::Pointer::End(None) => {
                 check_end_ptr!(iter.end, data);
@@ -449,17 +872,17 @@ pub unsafe fn for_all_ensure<F: Fn(Vector) -> Vector>(data: &[u8], cond: F) -> b
 #[inline(always)]
 pub unsafe fn ensure_requirements<R: crate::require::Requirement>(data: &[u8], mut req: R) -> R {
     let (vector, mut iter) = sealed::init_scan(data);
-    req.check(vector);
+    super::check_requirements(&mut req, vector);
 
     loop {
         match iter.next() {
             sealed::Pointer::Aligned((vector, _)) => {
                 check_end_ptr!(iter.end, data);
-                req.check(vector);
+                super::check_requirements(&mut req, vector);
             },
             sealed::Pointer::End(Some((vector, _))) => {
                 check_end_ptr!(iter.end, data);
-                req.check(vector);
+                super::check_requirements(&mut req, vector);
                 break req;
             },
             sealed::Pointer::End(None) => {
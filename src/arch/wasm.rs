@@ -6,6 +6,7 @@ compile_error!(
     you must enable the `experimental` feature flag."
 );
 pub struct MoveMask(u64);
+pub const WIDTH: usize = 16;
 pub type Vector = v128;
 
 pub type Ptr = Vector;
@@ -38,6 +39,31 @@ impl MoveMask {
     pub const fn trailing_ones(&self) -> u32 {
         self.0.trailing_ones()
     }
+    /// The number of set lanes
+    #[inline(always)] #[must_use]
+    pub const fn count_ones(&self) -> u32 {
+        self.0.count_ones()
+    }
+    /// The number of unset lanes preceding the highest set lane
+    #[inline(always)] #[must_use]
+    pub const fn leading_zeros(&self) -> u32 {
+        self.0.leading_zeros() >> 2
+    }
+    /// Unset the lowest lane which is set
+    #[inline(always)] #[must_use]
+    pub const fn clear_lowest(&self) -> Self {
+        Self(self.0 & self.0.wrapping_sub(1))
+    }
+    /// Unset every lane below `lane`
+    #[inline(always)] #[must_use]
+    pub const fn lanes_from(&self, lane: u32) -> Self {
+        if lane >= 16 { Self(0) } else { Self(self.0 & (u64::MAX << (lane << 2))) }
+    }
+    /// Unset every lane at or above `lane`
+    #[inline(always)] #[must_use]
+    pub const fn lanes_below(&self, lane: u32) -> Self {
+        if lane >= 16 { Self(self.0) } else { Self(self.0 & !(u64::MAX << (lane << 2))) }
+    }
 }
 
 impl_bit_ops!(MoveMask);
@@ -72,6 +98,44 @@ pub unsafe fn less_than(a: Vector, b: Vector) -> Vector { f32x4_lt(a, b) }
 #[inline(always)] #[must_use]
 pub unsafe fn splat(a: u8) -> Vector { i8x16_splat(a as i8) }
 
+#[inline(always)] #[must_use]
+pub unsafe fn sub(a: Vector, b: Vector) -> Vector { i8x16_sub(a, b) }
+
+#[inline(always)] #[must_use]
+pub unsafe fn saturating_sub(a: Vector, b: Vector) -> Vector { u8x16_sub_sat(a, b) }
+
+#[inline(always)] #[must_use]
+pub unsafe fn high_nibbles(a: Vector) -> Vector { u8x16_shr(a, 4) }
+
+/// Select the byte of the `table` at each index of `idx`, each index must be below 16
+#[inline(always)] #[must_use]
+pub unsafe fn shuffle(table: Vector, idx: Vector) -> Vector { i8x16_swizzle(table, idx) }
+
+/// Shift each byte of `data` up `N` lanes, filling the lowest `N` lanes with the highest `N` lanes
+/// of `prev`, `N` must be within `1..16`
+#[inline(always)] #[must_use]
+pub unsafe fn shift_in<const N: usize>(data: Vector, prev: Vector) -> Vector {
+    // lanes `0..16` of the shuffle select from `data`, `16..32` from `prev`, so lanes below `N`
+    // wrap around into `prev`
+    macro_rules! lane {
+        ($n:literal, $i:literal) => { ($i + 32 - $n) % 32 };
+    }
+    macro_rules! shift {
+        ($($n:literal),*) => {
+            match N {
+                $($n => i8x16_shuffle::<
+                    { lane!($n, 0) }, { lane!($n, 1) }, { lane!($n, 2) }, { lane!($n, 3) },
+                    { lane!($n, 4) }, { lane!($n, 5) }, { lane!($n, 6) }, { lane!($n, 7) },
+                    { lane!($n, 8) }, { lane!($n, 9) }, { lane!($n, 10) }, { lane!($n, 11) },
+                    { lane!($n, 12) }, { lane!($n, 13) }, { lane!($n, 14) }, { lane!($n, 15) }
+                >(data, prev),)*
+                _ => unreachable!("shifts must be within 1..16")
+            }
+        };
+    }
+    shift!(1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15)
+}
+
 #[inline(always)] #[must_use]
 pub unsafe fn load_unchecked(data: *const Ptr) -> Vector {
     v128_load(data)
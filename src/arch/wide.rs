@@ -0,0 +1,72 @@
+//! The scans over AVX2 vectors, for builds targeting baseline `x86_64`
+//!
+//! These are the same scans as the backend selected at compile time, instantiated a second time
+//! with the [`avx2`] operations, and only run from functions compiled with AVX2 enabled once it
+//! has been detected, see `dispatch`. Conditions evaluate the AVX2 vectors through
+//! [`Condition::eval_avx2`].
+//!
+//! The context carried between vectors for conditions which look behind is built from the SSE2
+//! vectors, so those conditions are always scanned by the SSE2 backend, and the `Context` here is
+//! never read.
+//!
+//! [`avx2`]: super::avx2
+
+pub use super::avx2 as arch;
+use arch::Vector;
+use crate::Condition;
+
+cfg_verify!(
+    pub(crate) use arch::is_aligned;
+);
+
+/// The bytes preceding a vector, never read as conditions which look behind are not scanned here
+#[doc(hidden)]
+#[derive(Debug, Copy, Clone)]
+pub struct Context;
+
+impl Context {
+    #[inline(always)] #[must_use]
+    pub const fn start() -> Self {
+        Self
+    }
+
+    #[inline(always)] #[must_use]
+    pub const fn after(_prev: Vector) -> Self {
+        Self
+    }
+
+    /// # Safety
+    ///
+    /// Always safe, this is `unsafe` to match `condition::Context::at`
+    #[inline(always)] #[must_use]
+    pub const unsafe fn at(_data: &[u8], _offset: usize) -> Self {
+        Self
+    }
+}
+
+/// Evaluate the `cond` for each lane of the `data`
+///
+/// # Safety
+///
+/// AVX2 must be available, and the `cond` must not look behind.
+#[inline(always)]
+pub(crate) unsafe fn eval_in_context<C: Condition + ?Sized>(
+    cond: &C, data: Vector, _ctx: impl FnOnce() -> Context
+) -> Vector {
+    debug_assert_eq!(cond.lookbehind(), 0);
+    cond.eval_avx2(data)
+}
+
+/// Check the requirements against each 128 bit lane of the `data`, as they evaluate SSE2 vectors
+#[cfg(feature = "require")]
+#[inline(always)]
+unsafe fn check_requirements<R: crate::require::Requirement>(req: &mut R, data: Vector) {
+    let (low, high) = arch::split(data);
+    req.check(low);
+    req.check(high);
+}
+
+// the same scans, resolving `arch`, `Context` and friends to the items above
+#[allow(clippy::duplicate_mod)]
+#[path = "simd_scan.rs"]
+pub mod scan;
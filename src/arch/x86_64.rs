@@ -3,7 +3,8 @@
 use core::arch::x86_64::{
     __m128i,
     _mm_and_si128, _mm_cmpeq_epi8, _mm_cmpgt_epi8, _mm_cmplt_epi8, _mm_load_si128,
-    _mm_or_si128, _mm_set1_epi8, _mm_xor_si128, _mm_setzero_si128
+    _mm_or_si128, _mm_set1_epi8, _mm_xor_si128, _mm_setzero_si128, _mm_srli_epi16, _mm_sub_epi8,
+    _mm_subs_epu8
 };
 
 cfg_runtime!(
@@ -13,7 +14,12 @@ cfg_runtime!(
 );
 
 cfg_verify!(
-    use crate::arch::is_aligned;
+    // these vectors are also built alongside the AVX2 backend, where the crate's `is_aligned`
+    // takes its vectors
+    pub(crate) fn is_aligned(ptr: *const Ptr) -> bool {
+        byte_ptr(ptr).align_offset(WIDTH) == 0
+    }
+
     use mirai_annotations::{checked_precondition};
 
     // foreign specifications, these are pure, so we only have to inform `mirai` of their existence.
@@ -26,6 +32,7 @@ cfg_verify!(
     }
 );
 
+pub const WIDTH: usize = 16;
 pub type Vector = __m128i;
 pub type Ptr = Vector;
 pub const STEP: usize = 1;
@@ -54,6 +61,33 @@ impl MoveMask {
     pub const fn trailing_ones(&self) -> u32 {
         self.0.trailing_ones()
     }
+    /// The number of set lanes
+    #[inline(always)] #[must_use]
+    pub const fn count_ones(&self) -> u32 {
+        self.0.count_ones()
+    }
+    /// The number of unset lanes preceding the highest set lane
+    #[inline(always)] #[must_use]
+    pub const fn leading_zeros(&self) -> u32 {
+        // only the lower 16 bits are in use, shift them into the upper half. This also ensures the
+        // result is `MAX_TRAIL` when no lane is set.
+        (self.0 << 16).leading_zeros()
+    }
+    /// Unset the lowest lane which is set
+    #[inline(always)] #[must_use]
+    pub const fn clear_lowest(&self) -> Self {
+        Self(self.0 & self.0.wrapping_sub(1))
+    }
+    /// Unset every lane below `lane`
+    #[inline(always)] #[must_use]
+    pub const fn lanes_from(&self, lane: u32) -> Self {
+        Self(self.0 & (u32::MAX << lane))
+    }
+    /// Unset every lane at or above `lane`
+    #[inline(always)] #[must_use]
+    pub const fn lanes_below(&self, lane: u32) -> Self {
+        Self(self.0 & !(u32::MAX << lane))
+    }
 }
 
 impl_bit_ops!(MoveMask);
@@ -91,6 +125,71 @@ pub unsafe fn less_than(a: Vector, b: Vector) -> Vector { _mm_cmplt_epi8(a, b) }
 #[inline(always)] #[must_use]
 pub unsafe fn splat(a: u8) -> Vector { _mm_set1_epi8(a as i8) }
 
+#[inline(always)] #[must_use]
+pub unsafe fn sub(a: Vector, b: Vector) -> Vector { _mm_sub_epi8(a, b) }
+
+#[inline(always)] #[must_use]
+pub unsafe fn saturating_sub(a: Vector, b: Vector) -> Vector { _mm_subs_epu8(a, b) }
+
+// sse lacks 8 bit shifts, shift the 16 bit lanes and clear what crossed over from the neighbour
+#[inline(always)] #[must_use]
+pub unsafe fn high_nibbles(a: Vector) -> Vector { and(_mm_srli_epi16::<4>(a), splat(0x0F)) }
+
+/// Select the byte of the `table` at each index of `idx`, each index must be below 16
+#[cfg(target_feature = "ssse3")]
+#[inline(always)] #[must_use]
+pub unsafe fn shuffle(table: Vector, idx: Vector) -> Vector {
+    core::arch::x86_64::_mm_shuffle_epi8(table, idx)
+}
+
+/// Select the byte of the `table` at each index of `idx`, each index must be below 16
+///
+/// sse2 lacks a byte shuffle, so the 16 bytes of the `table` are narrowed down by each bit of the
+/// index in turn, from the lowest. Each step keeps the even entry where the bit is clear and
+/// flips it to the odd entry where set, a constant number of instructions though far more than
+/// the single `pshufb`.
+#[cfg(not(target_feature = "ssse3"))]
+#[inline(always)] #[must_use]
+pub unsafe fn shuffle(table: Vector, idx: Vector) -> Vector {
+    let table = core::mem::transmute::<Vector, [u8; 16]>(table);
+    let mut entries = [splat(0); 16];
+    for (entry, byte) in entries.iter_mut().zip(table) {
+        *entry = splat(byte);
+    }
+
+    let mut len = 16;
+    let mut bit = 1;
+    while len > 1 {
+        let set = eq(and(idx, splat(bit)), splat(bit));
+        for i in 0..len / 2 {
+            let (even, odd) = (entries[2 * i], entries[2 * i + 1]);
+            entries[i] = xor(even, and(set, xor(even, odd)));
+        }
+        len /= 2;
+        bit <<= 1;
+    }
+    entries[0]
+}
+
+/// Shift each byte of `data` up `N` lanes, filling the lowest `N` lanes with the highest `N` lanes
+/// of `prev`, `N` must be within `1..16`
+#[inline(always)] #[must_use]
+pub unsafe fn shift_in<const N: usize>(data: Vector, prev: Vector) -> Vector {
+    // the shifts take an immediate, so each `N` is spelled out
+    macro_rules! shift {
+        ($($n:literal),*) => {
+            match N {
+                $($n => _mm_or_si128(
+                    core::arch::x86_64::_mm_slli_si128::<$n>(data),
+                    core::arch::x86_64::_mm_srli_si128::<{16 - $n}>(prev)
+                ),)*
+                _ => unreachable!("shifts must be within 1..16")
+            }
+        };
+    }
+    shift!(1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15)
+}
+
 #[inline(always)] #[must_use]
 pub unsafe fn load_unchecked(ptr: *const Ptr) -> Vector {
     _mm_loadu_si128(ptr)
@@ -107,7 +206,7 @@ pub unsafe fn load_aligned(ptr: *const Ptr) -> Vector {
 
 #[inline(always)] #[must_use]
 pub unsafe fn maybe_aligned_load(ptr: *const u8) -> Vector {
-    if ptr.align_offset(super::WIDTH) == 0 {
+    if ptr.align_offset(WIDTH) == 0 {
         unsafe { load_aligned(simd_ptr(ptr)) }
     } else {
         unsafe { load_unchecked(simd_ptr(ptr)) }
@@ -115,7 +214,7 @@ pub unsafe fn maybe_aligned_load(ptr: *const u8) -> Vector {
 }
 
 #[inline(always)] #[must_use]
-pub fn load(data: &[u8; super::WIDTH]) -> Vector {
+pub fn load(data: &[u8; WIDTH]) -> Vector {
     // SAFETY: the WIDTH is ensured by the type
     unsafe { maybe_aligned_load(data.as_ptr()) }
 }
@@ -0,0 +1,649 @@
+//! Conditions for arbitrary sets of bytes via nibble lookup
+//!
+//! Each byte is split into its low and high nibble, both index a 16 byte table via a shuffle
+//! (`pshufb` on x86_64 with SSSE3, `vqtbl1q_u8` on aarch64, `i8x16_swizzle` on WASM). The high
+//! nibbles sharing the same set of low nibbles form a class, each class is assigned a bit, the low
+//! nibble table holding the classes each low nibble belongs to and the high nibble table the class
+//! of each high nibble. A byte is in the set if the two lookups share a bit.
+//!
+//! There can be at most 16 classes, when there are more than 8 a second pair of tables is used, so
+//! any set costs at most four shuffles.
+//!
+//! Without a native shuffle (SSE2 without SSSE3, and the fallback) each shuffle is emulated with
+//! far more instructions. Both [`byte_set!`] and [`ByteSet`], for sets only known at runtime, use
+//! the nibble tables only when the set is not a single byte or range, and where the backend lacks
+//! a native shuffle only when the set has too many runs of bytes to compare for each. The tables
+//! are still built for those sets, as the AVX2 scans selected at runtime look the bytes up with
+//! the native `vpshufb`.
+//!
+//! [`byte_set!`]: crate::byte_set
+
+use crate::arch::{self, Vector};
+use crate::{truth_table, Condition};
+use core::fmt;
+use core::ops::RangeInclusive;
+
+/// Check each byte of the `$data` for membership of the `$nibbles`, with the operations of the
+/// backend `$arch`
+macro_rules! lookup {
+    ($arch:ident, $nibbles:expr, $data:expr) => {{
+        let (nibbles, data) = ($nibbles, $data);
+        let low = $arch::and(data, $arch::splat(0x0F));
+        let high = $arch::high_nibbles(data);
+
+        let mut res = $arch::and(
+            $arch::shuffle($arch::load(&nibbles.lo[0]), low),
+            $arch::shuffle($arch::load(&nibbles.hi[0]), high)
+        );
+        if nibbles.wide {
+            res = $arch::or(res, $arch::and(
+                $arch::shuffle($arch::load(&nibbles.lo[1]), low),
+                $arch::shuffle($arch::load(&nibbles.hi[1]), high)
+            ));
+        }
+
+        $arch::not($arch::eq(res, $arch::splat(0)))
+    }};
+}
+
+/// The nibble lookup tables for a set of bytes, see [`byte_set`]
+///
+/// [`byte_set`]: crate::byte_set
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Nibbles {
+    lo: [[u8; 16]; 2],
+    hi: [[u8; 16]; 2],
+    /// If more than 8 classes were needed, requiring the second pair of tables
+    wide: bool
+}
+
+impl Nibbles {
+    /// Build the lookup tables for the bytes set in the `table`
+    #[must_use]
+    pub const fn new(table: &[bool; 256]) -> Self {
+        // the low nibbles present for each high nibble
+        let mut rows = [0u16; 16];
+        let mut byte = 0;
+        while byte < 256 {
+            if table[byte] { rows[byte >> 4] |= 1 << (byte & 0x0F); }
+            byte += 1;
+        }
+
+        let mut classes = [0u16; 16];
+        let mut len = 0;
+        let mut lo = [[0u8; 16]; 2];
+        let mut hi = [[0u8; 16]; 2];
+
+        let mut high = 0;
+        while high < 16 {
+            if rows[high] != 0 {
+                let mut class = 0;
+                while class < len && classes[class] != rows[high] { class += 1; }
+
+                if class == len {
+                    classes[len] = rows[high];
+                    len += 1;
+
+                    let mut low = 0;
+                    while low < 16 {
+                        if rows[high] & (1 << low) != 0 { lo[class / 8][low] |= 1 << (class % 8); }
+                        low += 1;
+                    }
+                }
+
+                hi[class / 8][high] = 1 << (class % 8);
+            }
+            high += 1;
+        }
+
+        Self { lo, hi, wide: len > 8 }
+    }
+
+    /// Check if the `byte` is in the set
+    #[inline] #[must_use]
+    pub const fn contains(&self, byte: u8) -> bool {
+        let (low, high) = ((byte & 0x0F) as usize, (byte >> 4) as usize);
+        (self.lo[0][low] & self.hi[0][high]) | (self.lo[1][low] & self.hi[1][high]) != 0
+    }
+
+    /// If the set needs the second pair of tables
+    #[cfg(feature = "alloc")]
+    #[inline(always)]
+    pub(crate) const fn is_wide(&self) -> bool {
+        self.wide
+    }
+
+    /// Check each byte of the `data` for membership
+    #[inline(always)]
+    pub(crate) unsafe fn eval(&self, data: Vector) -> Vector {
+        lookup!(arch, self, data)
+    }
+
+}
+
+impl Condition for Nibbles {
+    #[inline(always)]
+    fn eval_vector(&self, data: Vector) -> Vector {
+        unsafe { self.eval(data) }
+    }
+    #[inline(always)]
+    fn eval_byte(&self, byte: u8) -> bool { self.contains(byte) }
+    #[inline(always)]
+    fn scalar_fast(&self) -> bool { true }
+    #[inline]
+    fn describe(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&ByteSet::from_cond(*self), f)
+    }
+    eval_avx2!(|self, data| lookup!(avx2, self, data));
+}
+
+/// The most runs of bytes a [`ByteSet`] compares for one by one, where the backend lacks a native
+/// shuffle
+const MAX_RUNS: usize = 8;
+
+/// How a [`ByteSet`] checks for membership, the cheapest which can represent the set
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Strategy {
+    /// The set is empty
+    Never,
+    /// The set holds a single byte
+    Eq(u8),
+    /// The set holds each byte from `min` to `min + span`
+    Range { min: u8, span: u8 },
+    /// The first `len` runs of bytes, as in `Range`, only used where the backend lacks a native
+    /// shuffle. The AVX2 scans selected at runtime have one, so look the bytes up in `nibbles`
+    Runs { runs: [(u8, u8); MAX_RUNS], len: u8, nibbles: Nibbles },
+    /// The set is anything else
+    Nibbles(Nibbles)
+}
+
+/// Each byte of the `$data` from `$min` to `$min + $span`, with the operations of the backend
+/// `$arch`
+macro_rules! in_range {
+    ($arch:ident, $data:expr, $min:expr, $span:expr) => {
+        // shift the range to start at zero, then anything which does not exceed the span is in the
+        // range, without needing unsigned comparisons.
+        $arch::eq(
+            $arch::saturating_sub($arch::sub($data, $arch::splat($min)), $arch::splat($span)),
+            $arch::splat(0)
+        )
+    };
+}
+
+/// Check each byte of the `$data` for membership with the `$strategy`, with the operations of the
+/// backend `$arch`
+macro_rules! membership {
+    ($arch:ident, $strategy:expr, $data:expr) => {{
+        let data = $data;
+        match $strategy {
+            Strategy::Never => $arch::splat(0),
+            Strategy::Eq(byte) => $arch::eq(data, $arch::splat(*byte)),
+            Strategy::Range { min, span } => in_range!($arch, data, *min, *span),
+            Strategy::Runs { runs, len, .. } => {
+                let mut res = $arch::splat(0);
+                for (min, span) in &runs[..*len as usize] {
+                    res = $arch::or(res, in_range!($arch, data, *min, *span));
+                }
+                res
+            },
+            Strategy::Nibbles(nibbles) => lookup!($arch, nibbles, data)
+        }
+    }};
+}
+
+impl Strategy {
+    #[inline(always)]
+    pub(crate) unsafe fn eval(&self, data: Vector) -> Vector {
+        membership!(arch, self, data)
+    }
+}
+
+cfg_dispatch!(
+    impl Strategy {
+        #[inline(always)]
+        pub(crate) unsafe fn eval_avx2(&self, data: arch::avx2::Vector) -> arch::avx2::Vector {
+            use arch::avx2;
+            match self {
+                // the native shuffle costs the same however many runs there are
+                Self::Runs { nibbles, .. } => lookup!(avx2, nibbles, data),
+                _ => membership!(avx2, self, data)
+            }
+        }
+    }
+);
+
+/// A set of bytes built at runtime
+///
+/// Unlike [`range`] or [`byte_set`], which require the set to be known at compile time, a
+/// `ByteSet` can be built from configuration. Once built, [`compile`] picks the cheapest way to
+/// check membership: a single comparison for one byte, a subtraction and comparison for one range,
+/// or nibble lookup tables for anything else. Where the backend lacks a native shuffle (SSE2
+/// without SSSE3, and the fallback) the lookup costs far more, so sets of up to eight runs of
+/// bytes compare for each run instead.
+///
+/// # Example
+///
+/// ```
+/// use swift_check::{ByteSet, for_all_ensure, search};
+///
+/// // loaded at startup
+/// let allowed = b"_-.";
+///
+/// let mut set = ByteSet::from_bytes(allowed);
+/// set.insert_range(b'a'..=b'z');
+/// set.insert_range(b'0'..=b'9');
+///
+/// let class = set.compile();
+/// assert!(for_all_ensure(b"my-config_name.v2", class));
+/// assert_eq!(search(b"my config", swift_check::not(class)), Some(2));
+/// ```
+///
+/// [`range`]: crate::range
+/// [`byte_set`]: crate::byte_set
+/// [`compile`]: ByteSet::compile
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct ByteSet {
+    bits: [u128; 2]
+}
+
+impl ByteSet {
+    /// Create a new, empty, `ByteSet`
+    #[inline] #[must_use]
+    pub const fn new() -> Self {
+        Self { bits: [0; 2] }
+    }
+
+    /// Create a `ByteSet` holding each of the `bytes`
+    #[must_use]
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        let mut set = Self::new();
+        for byte in bytes { set.insert(*byte); }
+        set
+    }
+
+    /// Create a `ByteSet` holding each byte set in the `table`, the `const` equivalent of `From`
+    #[doc(hidden)] #[must_use]
+    pub const fn from_table(table: &[bool; 256]) -> Self {
+        let mut bits = [0u128; 2];
+        let mut byte = 0;
+        while byte < 256 {
+            if table[byte] { bits[byte >> 7] |= 1 << (byte & 0x7F); }
+            byte += 1;
+        }
+        Self { bits }
+    }
+
+    /// Create a `ByteSet` holding each byte which meets the `cond`, see [`truth_table`]
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swift_check::{ByteSet, any, range, eq};
+    ///
+    /// let ident = ByteSet::from_cond(any!(range!(b'a'..=b'z'), range!(b'0'..=b'9'), eq(b'_')));
+    /// assert_eq!(ident.to_string(), "[0-9_a-z]");
+    ///
+    /// // what differs between two definitions of the class
+    /// let other = ByteSet::from_cond(any!(range!(b'a'..=b'z'), range!(b'0'..=b'9'), eq(b'-')));
+    /// assert_eq!(ident.symmetric_difference(&other).to_string(), r"[\x2D_]");
+    /// ```
+    ///
+    /// [`truth_table`]: crate::truth_table
+    #[must_use]
+    pub fn from_cond(cond: impl Condition) -> Self {
+        Self::from(truth_table(cond))
+    }
+
+    /// Add the `byte` to the set
+    #[inline]
+    pub fn insert(&mut self, byte: u8) {
+        self.bits[(byte >> 7) as usize] |= 1 << (byte & 0x7F);
+    }
+
+    /// Add each byte in the `range` to the set
+    #[inline]
+    pub fn insert_range(&mut self, range: RangeInclusive<u8>) {
+        for byte in range { self.insert(byte); }
+    }
+
+    /// Check if the `byte` is in the set
+    #[inline] #[must_use]
+    pub const fn contains(&self, byte: u8) -> bool {
+        self.bits[(byte >> 7) as usize] & (1 << (byte & 0x7F)) != 0
+    }
+
+    /// The number of bytes in the set
+    #[inline] #[must_use]
+    pub const fn len(&self) -> usize {
+        (self.bits[0].count_ones() + self.bits[1].count_ones()) as usize
+    }
+
+    /// `true` if the set holds no bytes
+    #[inline] #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.bits[0] == 0 && self.bits[1] == 0
+    }
+
+    /// The bytes in exactly one of the two sets
+    #[inline] #[must_use]
+    pub const fn symmetric_difference(&self, other: &Self) -> Self {
+        Self { bits: [self.bits[0] ^ other.bits[0], self.bits[1] ^ other.bits[1]] }
+    }
+
+    /// The membership of each byte
+    const fn table(&self) -> [bool; 256] {
+        let mut table = [false; 256];
+        let mut byte = 0;
+        while byte < 256 {
+            table[byte] = self.contains(byte as u8);
+            byte += 1;
+        }
+        table
+    }
+
+    /// Pick the cheapest strategy for checking membership
+    const fn strategy(&self) -> Strategy {
+        if self.is_empty() { return Strategy::Never; }
+
+        let min = if self.bits[0] != 0 {
+            self.bits[0].trailing_zeros()
+        } else {
+            128 + self.bits[1].trailing_zeros()
+        } as u8;
+        let max = if self.bits[1] != 0 {
+            255 - self.bits[1].leading_zeros()
+        } else {
+            127 - self.bits[0].leading_zeros()
+        } as u8;
+
+        if self.len() == (max - min) as usize + 1 {
+            if min == max { Strategy::Eq(min) } else { Strategy::Range { min, span: max - min } }
+        } else if !arch::NATIVE_SHUFFLE {
+            match self.runs() {
+                Some(runs) => runs,
+                None => Strategy::Nibbles(Nibbles::new(&self.table()))
+            }
+        } else {
+            Strategy::Nibbles(Nibbles::new(&self.table()))
+        }
+    }
+
+    /// The runs of bytes in the set, `None` if there are more than `MAX_RUNS`
+    pub(crate) const fn runs(&self) -> Option<Strategy> {
+        let mut runs = [(0u8, 0u8); MAX_RUNS];
+        let mut len = 0;
+        let mut byte = 0;
+        while byte < 256 {
+            if !self.contains(byte as u8) { byte += 1; continue; }
+            if len == MAX_RUNS { return None; }
+
+            let start = byte;
+            while byte < 256 && self.contains(byte as u8) { byte += 1; }
+            runs[len] = (start as u8, (byte - 1 - start) as u8);
+            len += 1;
+        }
+        Some(Strategy::Runs { runs, len: len as u8, nibbles: Nibbles::new(&self.table()) })
+    }
+
+    /// Prepare the set for checking membership, see [`ByteClass`]
+    #[inline] #[must_use]
+    pub const fn compile(&self) -> ByteClass {
+        ByteClass { set: *self, strategy: self.strategy() }
+    }
+}
+
+/// A [`ByteSet`] prepared for checking membership
+///
+/// The `ByteClass` is a copy of the set, later changes to the [`ByteSet`] do not affect it. It
+/// still answers [`contains`] for a single byte, which lets short inputs skip the vector entirely.
+///
+/// # Example
+///
+/// ```
+/// use swift_check::{ByteSet, for_all_ensure, search};
+///
+/// let class = ByteSet::from_bytes(b"0123456789abcdef").compile();
+/// assert!(for_all_ensure(b"deadbeef", class));
+/// assert_eq!(search(b"c0ffee!", swift_check::not(class)), Some(6));
+/// assert!(class.contains(b'a'));
+/// ```
+///
+/// [`contains`]: ByteClass::contains
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ByteClass {
+    set: ByteSet,
+    strategy: Strategy
+}
+
+impl ByteClass {
+    /// The set of bytes this class holds
+    #[inline] #[must_use]
+    pub const fn byte_set(&self) -> ByteSet {
+        self.set
+    }
+
+    /// Check if the `byte` is in the class
+    #[inline] #[must_use]
+    pub const fn contains(&self, byte: u8) -> bool {
+        self.set.contains(byte)
+    }
+}
+
+impl From<ByteSet> for ByteClass {
+    #[inline]
+    fn from(set: ByteSet) -> Self {
+        set.compile()
+    }
+}
+
+impl Condition for ByteClass {
+    #[inline(always)]
+    fn eval_vector(&self, data: Vector) -> Vector {
+        unsafe { self.strategy.eval(data) }
+    }
+    #[inline(always)]
+    fn eval_byte(&self, byte: u8) -> bool { self.contains(byte) }
+    #[inline(always)]
+    fn scalar_fast(&self) -> bool { true }
+    #[inline]
+    fn describe(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { fmt::Display::fmt(&self.set, f) }
+    eval_avx2!(|self, data| self.strategy.eval_avx2(data));
+}
+
+impl From<[bool; 256]> for ByteSet {
+    /// Create a `ByteSet` from the membership of each byte
+    #[inline]
+    fn from(table: [bool; 256]) -> Self {
+        Self::from_table(&table)
+    }
+}
+
+impl fmt::Display for ByteSet {
+    /// Write the set as a character class, with runs of bytes as ranges and anything other than
+    /// ASCII letters, digits and `_` escaped
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn write_byte(f: &mut fmt::Formatter<'_>, byte: u8) -> fmt::Result {
+            if byte.is_ascii_alphanumeric() || byte == b'_' {
+                write!(f, "{}", byte as char)
+            } else {
+                write!(f, "\\x{byte:02X}")
+            }
+        }
+
+        f.write_str("[")?;
+        let mut byte = 0u16;
+        while byte < 256 {
+            if !self.contains(byte as u8) { byte += 1; continue; }
+            let start = byte;
+            while byte < 256 && self.contains(byte as u8) { byte += 1; }
+            write_byte(f, start as u8)?;
+            match byte - start {
+                1 => {},
+                2 => write_byte(f, start as u8 + 1)?,
+                _ => {
+                    f.write_str("-")?;
+                    write_byte(f, (byte - 1) as u8)?;
+                }
+            }
+        }
+        f.write_str("]")
+    }
+}
+
+/// Check that each byte is in a set, described by a pattern
+///
+/// The set is compiled into a [`ByteClass`] at compile time, picking the same strategy as
+/// [`ByteSet::compile`]. A single byte or range costs one comparison. Anything else is checked
+/// with nibble lookup tables, where the backend has a native shuffle, costing the same however
+/// many branches there are. SSE2 without SSSE3, the default for `x86_64`, has no native shuffle,
+/// so sets of up to eight runs of bytes compare for each run, as [`any`] would. Once AVX2 is
+/// detected at runtime, the dispatched scans use the lookup for these sets too.
+///
+/// Larger sets pay for the emulated lookup on SSE2, around 50 instructions for each of the two or
+/// four shuffles, which is accepted as the cost of the default `x86_64` target. Enabling SSSE3, or
+/// running where AVX2 is detected, makes the lookup native.
+///
+/// # Arguments
+///
+/// * `pattern` - The bytes in the set, any pattern which could be used to match a `u8`, branches
+///   are separated by `|`
+///
+/// # Example
+///
+/// ```
+/// use swift_check::{for_all_ensure, search, byte_set, not};
+///
+/// let base64 = byte_set!(b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'+' | b'/' | b'=');
+///
+/// assert!(for_all_ensure(b"c3dpZnQtY2hlY2s=", base64));
+/// assert_eq!(search(b"c3dpZnQ-Y2hlY2s=", not(base64)), Some(7));
+/// ```
+///
+/// [`any`]: crate::any
+#[macro_export]
+macro_rules! byte_set {
+    ($pattern:pat $(,)?) => {
+        $crate::__byte_class!($pattern)
+    };
+}
+
+/// The [`ByteClass`] for the bytes matching the pattern, built at compile time
+#[doc(hidden)] #[macro_export]
+macro_rules! __byte_class {
+    ($pattern:pat) => {{
+        const CLASS: $crate::ByteClass = $crate::ByteSet::from_table(&{
+            let mut table = [false; 256];
+            let mut byte = 0;
+            while byte < 256 {
+                table[byte] = ::core::matches!(byte as u8, $pattern);
+                byte += 1;
+            }
+            table
+        }).compile();
+        CLASS
+    }};
+}
+
+#[cfg(all(test, not(mirai)))]
+mod tests {
+    use super::*;
+    use crate::{for_all_ensure, search_all, range};
+    use quickcheck::quickcheck;
+
+    extern crate alloc;
+    use alloc::vec::Vec;
+
+    fn table_of(members: &[u8]) -> [bool; 256] {
+        let mut table = [false; 256];
+        for member in members { table[*member as usize] = true; }
+        table
+    }
+
+    quickcheck! {
+        fn byte_set_cond_matches_scalar(members: Vec<u8>, ranges: Vec<(u8, u8)>, s: Vec<u8>) -> bool {
+            let mut set = ByteSet::from_bytes(&members);
+            for (start, end) in ranges.into_iter().take(2) {
+                set.insert_range(start.min(end)..=start.max(end));
+            }
+            search_all(&s, set.compile())
+                .eq(s.iter().enumerate().filter(|(_, byte)| set.contains(**byte)).map(|(i, _)| i))
+        }
+        fn nibbles_contains_members(members: Vec<u8>) -> bool {
+            let table = table_of(&members);
+            let nibbles = Nibbles::new(&table);
+            (0..=255u8).all(|byte| nibbles.contains(byte) == table[byte as usize])
+        }
+        fn nibbles_cond_matches_scalar(members: Vec<u8>, s: Vec<u8>) -> bool {
+            let table = table_of(&members);
+            // ensure the data contains members of the set
+            let s: Vec<u8> = s.iter().zip(members.iter().cycle()).enumerate()
+                .map(|(i, (byte, member))| if i % 3 == 0 { *member } else { *byte })
+                .collect();
+            search_all(&s, Nibbles::new(&table))
+                .eq(s.iter().enumerate().filter(|(_, byte)| table[**byte as usize]).map(|(i, _)| i))
+        }
+    }
+
+    #[test]
+    fn byte_set_matches_pattern() {
+        let all: Vec<u8> = (0..=255).collect();
+        let is_ident = |byte: &u8| matches!(byte, b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'_');
+        let idents: Vec<u8> = all.iter().copied().filter(is_ident).collect();
+
+        let ident = byte_set!(b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'_');
+        assert!(for_all_ensure(&idents, ident));
+        assert!(search_all(&all, ident).eq(idents.iter().map(|byte| *byte as usize)));
+
+        // every high nibble with a distinct row, requiring both pairs of tables
+        let wide = byte_set!(0x00 | 0x11 | 0x22 | 0x33 | 0x44 | 0x55 | 0x66 | 0x77 | 0x88 | 0x99);
+        assert!(search_all(&all, wide).eq([0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99]));
+
+        assert_eq!(search_all(&all, byte_set!(_)).count(), 256);
+
+        // the same strategy as a set built at runtime, comparing for each run without a native
+        // shuffle
+        assert_eq!(ident.strategy, ByteSet::from_bytes(&idents).strategy());
+        assert_eq!(byte_set!(b'a'..=b'z').strategy, Strategy::Range { min: b'a', span: 25 });
+        assert_eq!(matches!(ident.strategy, Strategy::Nibbles(_)), arch::NATIVE_SHUFFLE);
+    }
+
+    #[test]
+    fn byte_set_strategies() {
+        let mut set = ByteSet::new();
+        assert_eq!(set.strategy(), Strategy::Never);
+        set.insert(b'x');
+        assert_eq!(set.strategy(), Strategy::Eq(b'x'));
+        set.insert_range(b'a'..=b'w');
+        assert_eq!(set.strategy(), Strategy::Range { min: b'a', span: b'x' - b'a' });
+        set.insert(b'_');
+        if arch::NATIVE_SHUFFLE {
+            assert!(matches!(set.strategy(), Strategy::Nibbles(_)));
+        } else {
+            let mut runs = [(0, 0); MAX_RUNS];
+            runs[..2].copy_from_slice(&[(b'_', 0), (b'a', b'x' - b'a')]);
+            let nibbles = Nibbles::new(&set.table());
+            assert_eq!(set.strategy(), Strategy::Runs { runs, len: 2, nibbles });
+        }
+
+        // more runs than are worth comparing for one by one
+        let evens = ByteSet::from_bytes(&(0..=255).step_by(2).collect::<Vec<u8>>());
+        assert!(matches!(evens.strategy(), Strategy::Nibbles(_)));
+        assert!(search_all(&(0..=255).collect::<Vec<u8>>(), evens.compile()).eq((0..256).step_by(2)));
+
+        let mut all = ByteSet::new();
+        all.insert_range(0..=255);
+        assert_eq!(all.len(), 256);
+        assert_eq!(all.strategy(), Strategy::Range { min: 0, span: 255 });
+        assert!(for_all_ensure(&(0..=255).collect::<Vec<u8>>(), all.compile()));
+    }
+
+    #[test]
+    fn byte_set_display() {
+        use alloc::string::ToString;
+        assert_eq!(ByteSet::new().to_string(), "[]");
+        assert_eq!(ByteSet::from_bytes(b"ab").to_string(), "[ab]");
+        assert_eq!(ByteSet::from_bytes(b"abcz").to_string(), "[a-cz]");
+        assert_eq!(ByteSet::from_bytes(b" \n").to_string(), r"[\x0A\x20]");
+        assert_eq!(ByteSet::from_cond(range!(>= 0)).to_string(), r"[\x00-\xFF]");
+    }
+}
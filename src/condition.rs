@@ -0,0 +1,1511 @@
+//! Conditions which can be evaluated a vector or a byte at a time
+//!
+//! Every condition exposed by this crate implements [`Condition`], as does any closure taking and
+//! returning a [`Vector`]. The vector evaluation drives the scans, while the byte evaluation is
+//! used where the input is too short to fill a vector and evaluating each byte is cheaper than
+//! assembling a partial load.
+//!
+//! Conditions also describe themselves for diagnostics, composed conditions from their parts, such
+//! as `'a'..='z' or '_'`. Wrapping a condition with [`named`](crate::named) replaces its
+//! description with something meaningful to the reader, like `hex digit`.
+
+use crate::arch::{self, MoveMask, Vector};
+use core::fmt;
+
+/// A condition over bytes
+///
+/// # Example
+///
+/// ```
+/// use swift_check::{Condition, any, eq, range, arch::{load, MoveMask}};
+///
+/// let cond = any!(eq(b'_'), range!(b'a'..=b'z'));
+///
+/// assert!(cond.eval_byte(b'_'));
+/// assert!(!cond.eval_byte(b'A'));
+///
+/// let mask = unsafe { MoveMask::new(cond.eval_vector(load(b"snake_case_ident"))) };
+/// assert!(mask.all_bits_set());
+/// ```
+pub trait Condition {
+    /// Evaluate the condition for each lane of the `data`, setting every bit of the lanes which
+    /// meet it
+    fn eval_vector(&self, data: Vector) -> Vector;
+
+    /// Evaluate the condition for a single byte
+    ///
+    /// By default this splats the `byte` across a vector and evaluates that.
+    #[inline(always)]
+    fn eval_byte(&self, byte: u8) -> bool {
+        unsafe { MoveMask::new(self.eval_vector(arch::splat(byte))).any_bit_set() }
+    }
+
+    /// Whether [`eval_byte`] is cheaper than a vector evaluation, used to decide how to evaluate
+    /// input which does not fill a vector
+    ///
+    /// [`for_all_ensure_ct`] ignores this, as the byte evaluation may branch on the data.
+    ///
+    /// [`eval_byte`]: Condition::eval_byte
+    /// [`for_all_ensure_ct`]: crate::for_all_ensure_ct
+    #[inline(always)]
+    fn scalar_fast(&self) -> bool {
+        false
+    }
+
+    /// Write a human readable description of the condition
+    ///
+    /// Conditions from this crate describe themselves from their parts, closures are described as
+    /// `condition`. See [`named`](crate::named) for giving a condition its own description.
+    fn describe(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("condition")
+    }
+
+    /// The word joining the parts of the description, `None` if the description is a single part,
+    /// used to decide where a composed description needs parentheses
+    #[doc(hidden)]
+    #[inline(always)]
+    fn connective(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Evaluate the condition for each lane of the `data`, where `ctx` holds the bytes preceding
+    /// it, only conditions with a [`lookbehind`] look at the `ctx`
+    ///
+    /// [`lookbehind`]: Condition::lookbehind
+    #[doc(hidden)]
+    #[inline(always)]
+    fn eval_context(&self, data: Vector, _ctx: Context) -> Vector {
+        self.eval_vector(data)
+    }
+
+    /// How many bytes before each lane the condition looks at, the scans only carry the preceding
+    /// bytes across vectors for conditions which look behind
+    #[doc(hidden)]
+    #[inline(always)]
+    fn lookbehind(&self) -> usize {
+        0
+    }
+
+    /// Evaluate the condition for each lane of an AVX2 vector, for the scans selected at runtime
+    /// on baseline `x86_64` builds
+    ///
+    /// By default each 128 bit lane is evaluated as an SSE2 vector, the conditions of this crate
+    /// evaluate the whole vector at once.
+    ///
+    /// # Safety
+    ///
+    /// AVX2 must be available.
+    #[cfg(all(
+        feature = "std", feature = "simd", target_arch = "x86_64", target_feature = "sse2",
+        not(target_feature = "avx2")
+    ))]
+    #[doc(hidden)]
+    #[inline(always)]
+    unsafe fn eval_avx2(&self, data: arch::avx2::Vector) -> arch::avx2::Vector {
+        let (low, high) = arch::avx2::split(data);
+        arch::avx2::join(self.eval_vector(low), self.eval_vector(high))
+    }
+
+    /// Borrow the condition, leaving it usable after passing it to something which takes ownership
+    #[inline(always)]
+    fn by_ref(&self) -> ByRef<'_, Self> where Self: Sized {
+        ByRef(self)
+    }
+
+    /// The [`describe`] output of the condition, as something which can be displayed
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swift_check::{Condition, any, eq, named, range};
+    ///
+    /// let cond = any!(named("letter", range!(b'a'..=b'z')), named("digit", range!(b'0'..=b'9')), eq(b'_'));
+    /// assert_eq!(cond.description().to_string(), "letter or digit or '_'");
+    /// ```
+    ///
+    /// [`describe`]: Condition::describe
+    #[inline(always)]
+    fn description(&self) -> Description<'_, Self> where Self: Sized {
+        Description(self)
+    }
+}
+
+/// A condition which never looks behind, as [`prev`] does, so each vector can be evaluated on its
+/// own
+///
+/// Requirements, see [`Requires`], are checked a vector at a time and may be given their input in
+/// chunks, so only accept these conditions. Every condition of this crate other than [`prev`], and
+/// anything composed of them, implements this, as does any closure.
+///
+/// [`prev`]: crate::prev
+/// [`Requires`]: crate::require::Requires
+pub trait NoLookbehind {}
+
+/// The bytes preceding a vector, for conditions which look behind, see [`prev`](crate::prev)
+#[doc(hidden)]
+#[derive(Debug, Copy, Clone)]
+pub struct Context {
+    /// The vector of bytes immediately preceding the one being evaluated
+    prev: Vector,
+    /// Set in each lane of `prev` holding a byte of the input, rather than what comes before it
+    exists: Vector,
+    /// Set in each lane of the vector being evaluated holding a byte of the input, only unset
+    /// where an enclosing [`Prev`] shifted in lanes preceding the input
+    present: Vector
+}
+
+impl Context {
+    /// The context at the start of the input, where nothing precedes the vector
+    #[inline(always)] #[must_use]
+    pub fn start() -> Self {
+        unsafe { Self { prev: arch::splat(0), exists: arch::splat(0), present: arch::splat(0xFF) } }
+    }
+
+    /// The context of a vector following `prev` in the input
+    #[inline(always)] #[must_use]
+    pub fn after(prev: Vector) -> Self {
+        unsafe { Self { prev, exists: arch::splat(0xFF), present: arch::splat(0xFF) } }
+    }
+
+    /// The context of the vector loaded from `offset` within the `data`
+    ///
+    /// # Safety
+    ///
+    /// `offset` must not exceed the length of the `data`
+    #[inline] #[must_use]
+    pub unsafe fn at(data: &[u8], offset: usize) -> Self {
+        match offset {
+            0 => Self::start(),
+            _ if offset >= arch::WIDTH => Self::after(arch::load_at(data, offset - arch::WIDTH)),
+            // fewer bytes than a vector precede the offset, these fill the highest lanes
+            _ => {
+                let (mut prev, mut exists) = ([0u8; arch::WIDTH], [0u8; arch::WIDTH]);
+                prev[arch::WIDTH - offset..].copy_from_slice(data.get_unchecked(..offset));
+                exists[arch::WIDTH - offset..].fill(0xFF);
+                Self {
+                    prev: arch::load_at(&prev, 0),
+                    exists: arch::load_at(&exists, 0),
+                    present: arch::splat(0xFF)
+                }
+            }
+        }
+    }
+
+    /// Which lanes of the vector shifted up `N` lanes hold a byte of the input
+    #[inline(always)] #[must_use]
+    unsafe fn shifted_present<const N: usize>(self) -> Vector {
+        arch::shift_in::<N>(self.present, self.exists)
+    }
+
+    /// The context of the vector shifted up `N` lanes, with the lanes shifted out of `prev` lost
+    #[inline(always)] #[must_use]
+    unsafe fn shift<const N: usize>(self) -> Self {
+        Self {
+            prev: arch::shift_in::<N>(self.prev, arch::splat(0)),
+            exists: arch::shift_in::<N>(self.exists, arch::splat(0)),
+            present: self.shifted_present::<N>()
+        }
+    }
+}
+
+/// How many bytes a condition can look back in total, the scans only carry one vector across
+const MAX_LOOKBEHIND: usize = 16;
+
+/// Reject a `cond` looking back further than the bytes the scans carry across vectors, those bytes
+/// would otherwise be taken as preceding the input
+#[inline(always)]
+pub(crate) fn check_lookbehind<C: Condition + ?Sized>(cond: &C) {
+    assert!(cond.lookbehind() <= MAX_LOOKBEHIND, "`prev` can look back at most 16 bytes in total");
+}
+
+/// Evaluate the `cond` for each lane of the `data`, only building the `ctx` if the condition looks
+/// behind
+#[inline(always)]
+pub(crate) fn eval_in_context<C: Condition + ?Sized>(
+    cond: &C, data: Vector, ctx: impl FnOnce() -> Context
+) -> Vector {
+    if cond.lookbehind() == 0 { cond.eval_vector(data) } else { cond.eval_context(data, ctx()) }
+}
+
+/// Describe `cond` as an operand joined by `connective`, wrapped in parentheses where it joins its
+/// own parts by something else
+pub(crate) fn describe_operand(
+    cond: &impl Condition, connective: &str, f: &mut fmt::Formatter<'_>
+) -> fmt::Result {
+    match cond.connective() {
+        Some(inner) if inner != connective => {
+            f.write_str("(")?;
+            cond.describe(f)?;
+            f.write_str(")")
+        },
+        _ => cond.describe(f)
+    }
+}
+
+/// Describe a single byte, quoted if printable and in hex otherwise
+pub(crate) fn describe_byte(byte: u8, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if byte.is_ascii_graphic() || byte == b' ' {
+        write!(f, "'{}'", (byte as char).escape_default())
+    } else {
+        write!(f, "{byte:#04x}")
+    }
+}
+
+/// Describe the bytes from `min` up to, but excluding, `min + span`
+pub(crate) fn describe_range(min: u8, span: u16, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match span {
+        0 => f.write_str("nothing"),
+        1 => describe_byte(min, f),
+        256 => f.write_str("any byte"),
+        _ => {
+            describe_byte(min, f)?;
+            f.write_str("..=")?;
+            describe_byte((min as u16 + span - 1) as u8, f)
+        }
+    }
+}
+
+/// The description of a condition, see [`Condition::description`]
+#[derive(Debug)]
+pub struct Description<'c, C: ?Sized>(&'c C);
+
+impl<'c, C: Condition + ?Sized> fmt::Display for Description<'c, C> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.describe(f)
+    }
+}
+
+impl<F: Fn(Vector) -> Vector> Condition for F {
+    #[inline(always)]
+    fn eval_vector(&self, data: Vector) -> Vector {
+        self(data)
+    }
+}
+
+/// A borrowed condition, see [`Condition::by_ref`]
+#[derive(Debug)]
+#[repr(transparent)]
+pub struct ByRef<'c, C: ?Sized>(&'c C);
+
+impl<'c, C: ?Sized> Clone for ByRef<'c, C> {
+    #[inline]
+    fn clone(&self) -> Self { *self }
+}
+
+impl<'c, C: ?Sized> Copy for ByRef<'c, C> {}
+
+impl<'c, C: Condition + ?Sized> Condition for ByRef<'c, C> {
+    #[inline(always)]
+    fn eval_vector(&self, data: Vector) -> Vector { self.0.eval_vector(data) }
+    #[inline(always)]
+    fn eval_byte(&self, byte: u8) -> bool { self.0.eval_byte(byte) }
+    #[inline(always)]
+    fn scalar_fast(&self) -> bool { self.0.scalar_fast() }
+    #[inline]
+    fn describe(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { self.0.describe(f) }
+    #[inline(always)]
+    fn connective(&self) -> Option<&'static str> { self.0.connective() }
+    #[inline(always)]
+    fn eval_context(&self, data: Vector, ctx: Context) -> Vector { self.0.eval_context(data, ctx) }
+    #[inline(always)]
+    fn lookbehind(&self) -> usize { self.0.lookbehind() }
+    eval_avx2!(|self, data| self.0.eval_avx2(data));
+}
+
+/// A condition described by a name, see [`named`](crate::named)
+#[derive(Debug, Copy, Clone)]
+pub struct Named<C> {
+    name: &'static str,
+    cond: C
+}
+
+impl<C> Named<C> {
+    #[inline(always)]
+    pub(crate) const fn new(name: &'static str, cond: C) -> Self {
+        Self { name, cond }
+    }
+
+    /// The name describing the condition
+    #[inline] #[must_use]
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+impl<C: Condition> Condition for Named<C> {
+    #[inline(always)]
+    fn eval_vector(&self, data: Vector) -> Vector { self.cond.eval_vector(data) }
+    #[inline(always)]
+    fn eval_byte(&self, byte: u8) -> bool { self.cond.eval_byte(byte) }
+    #[inline(always)]
+    fn scalar_fast(&self) -> bool { self.cond.scalar_fast() }
+    #[inline]
+    fn describe(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.write_str(self.name) }
+    #[inline(always)]
+    fn eval_context(&self, data: Vector, ctx: Context) -> Vector { self.cond.eval_context(data, ctx) }
+    #[inline(always)]
+    fn lookbehind(&self) -> usize { self.cond.lookbehind() }
+    eval_avx2!(|self, data| self.cond.eval_avx2(data));
+}
+
+/// The byte equals a value, see [`eq`](crate::eq)
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Eq(u8);
+
+impl Eq {
+    #[inline(always)]
+    pub(crate) const fn new(expected: u8) -> Self {
+        Self(expected)
+    }
+}
+
+impl Condition for Eq {
+    #[inline(always)]
+    fn eval_vector(&self, data: Vector) -> Vector {
+        unsafe { arch::eq(data, arch::splat(self.0)) }
+    }
+    #[inline(always)]
+    fn eval_byte(&self, byte: u8) -> bool { byte == self.0 }
+    #[inline(always)]
+    fn scalar_fast(&self) -> bool { true }
+    #[inline]
+    fn describe(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { describe_byte(self.0, f) }
+    eval_avx2!(|self, data| avx2::eq(data, avx2::splat(self.0)));
+}
+
+/// The bits of the byte selected by a mask equal a value, see [`masked_eq`](crate::masked_eq)
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct MaskedEq {
+    mask: u8,
+    value: u8
+}
+
+impl MaskedEq {
+    #[inline(always)]
+    pub(crate) const fn new(mask: u8, value: u8) -> Self {
+        Self { mask, value }
+    }
+}
+
+impl Condition for MaskedEq {
+    #[inline(always)]
+    fn eval_vector(&self, data: Vector) -> Vector {
+        unsafe { arch::eq(arch::and(data, arch::splat(self.mask)), arch::splat(self.value)) }
+    }
+    #[inline(always)]
+    fn eval_byte(&self, byte: u8) -> bool { byte & self.mask == self.value }
+    #[inline(always)]
+    fn scalar_fast(&self) -> bool { true }
+    #[inline]
+    fn describe(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "byte & {:#04x} == {:#04x}", self.mask, self.value)
+    }
+    eval_avx2!(|self, data| avx2::eq(avx2::and(data, avx2::splat(self.mask)), avx2::splat(self.value)));
+}
+
+/// Any of the bits of the byte selected by a mask are set, see [`any_bits`](crate::any_bits)
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct AnyBits(u8);
+
+impl AnyBits {
+    #[inline(always)]
+    pub(crate) const fn new(mask: u8) -> Self {
+        Self(mask)
+    }
+}
+
+impl Condition for AnyBits {
+    #[inline(always)]
+    fn eval_vector(&self, data: Vector) -> Vector {
+        unsafe { arch::not(arch::eq(arch::and(data, arch::splat(self.0)), arch::splat(0))) }
+    }
+    #[inline(always)]
+    fn eval_byte(&self, byte: u8) -> bool { byte & self.0 != 0 }
+    #[inline(always)]
+    fn scalar_fast(&self) -> bool { true }
+    #[inline]
+    fn describe(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "byte & {:#04x} != 0x00", self.0)
+    }
+    eval_avx2!(|self, data| avx2::not(avx2::eq(avx2::and(data, avx2::splat(self.0)), avx2::splat(0))));
+}
+
+/// The byte lies within a range, see [`range!`](crate::range)
+#[derive(Debug, Copy, Clone)]
+pub struct Range<F> {
+    min: u8,
+    span: u16,
+    vector: F
+}
+
+impl<F> Range<F> {
+    /// The bytes from `min` up to, but excluding, `min + span`, where `vector` is the equivalent
+    /// vector condition
+    #[doc(hidden)]
+    #[inline(always)]
+    pub const fn new(min: u8, span: u16, vector: F) -> Self {
+        Self { min, span, vector }
+    }
+}
+
+impl<F: Fn(Vector) -> Vector> Condition for Range<F> {
+    #[inline(always)]
+    fn eval_vector(&self, data: Vector) -> Vector { (self.vector)(data) }
+    #[inline(always)]
+    fn eval_byte(&self, byte: u8) -> bool {
+        (byte.wrapping_sub(self.min) as u16) < self.span
+    }
+    #[inline(always)]
+    fn scalar_fast(&self) -> bool { true }
+    #[inline]
+    fn describe(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        describe_range(self.min, self.span, f)
+    }
+    // shift the range to start at zero, then anything which does not exceed the last byte of the
+    // span is in the range
+    eval_avx2!(|self, data| match self.span {
+        0 => avx2::splat(0),
+        span => avx2::eq(
+            avx2::saturating_sub(avx2::sub(data, avx2::splat(self.min)), avx2::splat((span - 1) as u8)),
+            avx2::splat(0)
+        )
+    });
+}
+
+/// The inner condition does not hold, see [`not`](crate::not)
+#[derive(Debug, Copy, Clone)]
+pub struct Not<C>(C);
+
+impl<C> Not<C> {
+    #[inline(always)]
+    pub(crate) const fn new(cond: C) -> Self {
+        Self(cond)
+    }
+}
+
+impl<C: Condition> Condition for Not<C> {
+    #[inline(always)]
+    fn eval_vector(&self, data: Vector) -> Vector {
+        unsafe { arch::not(self.0.eval_vector(data)) }
+    }
+    #[inline(always)]
+    fn eval_byte(&self, byte: u8) -> bool { !self.0.eval_byte(byte) }
+    #[inline(always)]
+    fn scalar_fast(&self) -> bool { self.0.scalar_fast() }
+    #[inline]
+    fn describe(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("not ")?;
+        describe_operand(&self.0, "not", f)
+    }
+    #[inline(always)]
+    fn eval_context(&self, data: Vector, ctx: Context) -> Vector {
+        unsafe { arch::not(self.0.eval_context(data, ctx)) }
+    }
+    #[inline(always)]
+    fn lookbehind(&self) -> usize { self.0.lookbehind() }
+    eval_avx2!(|self, data| avx2::not(self.0.eval_avx2(data)));
+}
+
+macro_rules! binary_condition {
+    ($(#[$meta:meta])* $name:ident, $vector:ident, $byte:tt, $connective:literal) => {
+        $(#[$meta])*
+        #[derive(Debug, Copy, Clone)]
+        pub struct $name<A, B>(A, B);
+
+        impl<A, B> $name<A, B> {
+            #[inline(always)]
+            pub(crate) const fn new(a: A, b: B) -> Self {
+                Self(a, b)
+            }
+        }
+
+        impl<A: Condition, B: Condition> Condition for $name<A, B> {
+            #[inline(always)]
+            fn eval_vector(&self, data: Vector) -> Vector {
+                unsafe { arch::$vector(self.0.eval_vector(data), self.1.eval_vector(data)) }
+            }
+            #[inline(always)]
+            fn eval_byte(&self, byte: u8) -> bool {
+                self.0.eval_byte(byte) $byte self.1.eval_byte(byte)
+            }
+            #[inline(always)]
+            fn scalar_fast(&self) -> bool {
+                self.0.scalar_fast() && self.1.scalar_fast()
+            }
+            #[inline]
+            fn describe(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                describe_operand(&self.0, $connective, f)?;
+                f.write_str(concat!(" ", $connective, " "))?;
+                describe_operand(&self.1, $connective, f)
+            }
+            #[inline(always)]
+            fn connective(&self) -> Option<&'static str> { Some($connective) }
+            #[inline(always)]
+            fn eval_context(&self, data: Vector, ctx: Context) -> Vector {
+                unsafe { arch::$vector(self.0.eval_context(data, ctx), self.1.eval_context(data, ctx)) }
+            }
+            #[inline(always)]
+            fn lookbehind(&self) -> usize {
+                self.0.lookbehind().max(self.1.lookbehind())
+            }
+            eval_avx2!(|self, data| avx2::$vector(self.0.eval_avx2(data), self.1.eval_avx2(data)));
+        }
+    };
+}
+
+binary_condition!(
+    /// Both conditions hold, see [`and`](crate::and) and [`all!`](crate::all)
+    And, and, &, "and"
+);
+binary_condition!(
+    /// Either condition holds, see [`or`](crate::or) and [`any!`](crate::any)
+    Or, or, |, "or"
+);
+binary_condition!(
+    /// Exactly one of the two conditions holds, see [`xor`](crate::xor)
+    Xor, xor, ^, "xor"
+);
+
+/// The conditions counted by [`Exactly`], [`AtLeast`] and [`AtMost`], as lists of nested pairs
+/// ending in `()`, joined by [`Group`], see [`exactly!`](crate::exactly)
+#[doc(hidden)]
+pub trait Tally {
+    /// The number of conditions
+    const LEN: usize;
+    /// Subtract one from each lane of `acc` for each condition the lane meets, saturating at zero
+    /// if `SATURATE`
+    ///
+    /// # Safety
+    ///
+    /// The same as the [`arch`] operations.
+    unsafe fn tally<const SATURATE: bool>(&self, data: Vector, ctx: Context, acc: Vector) -> Vector;
+    /// [`Tally::tally`] over an AVX2 vector, only dispatched to when no condition looks behind
+    ///
+    /// # Safety
+    ///
+    /// AVX2 must be available.
+    #[cfg(all(
+        feature = "std", feature = "simd", target_arch = "x86_64", target_feature = "sse2",
+        not(target_feature = "avx2")
+    ))]
+    unsafe fn tally_avx2<const SATURATE: bool>(
+        &self, data: arch::avx2::Vector, acc: arch::avx2::Vector
+    ) -> arch::avx2::Vector;
+    /// The number of conditions the `byte` meets
+    fn count(&self, byte: u8) -> usize;
+    /// Whether every condition is [`Condition::scalar_fast`]
+    fn scalar_fast(&self) -> bool;
+    /// The furthest [`Condition::lookbehind`] of the conditions
+    fn lookbehind(&self) -> usize;
+    /// Describe each condition, separated by commas, starting with one if not `first`
+    fn describe(&self, first: bool, f: &mut fmt::Formatter<'_>) -> fmt::Result;
+}
+
+impl Tally for () {
+    const LEN: usize = 0;
+    #[inline(always)]
+    unsafe fn tally<const SATURATE: bool>(&self, _data: Vector, _ctx: Context, acc: Vector) -> Vector {
+        acc
+    }
+    #[cfg(all(
+        feature = "std", feature = "simd", target_arch = "x86_64", target_feature = "sse2",
+        not(target_feature = "avx2")
+    ))]
+    #[inline(always)]
+    unsafe fn tally_avx2<const SATURATE: bool>(
+        &self, _data: arch::avx2::Vector, acc: arch::avx2::Vector
+    ) -> arch::avx2::Vector {
+        acc
+    }
+    #[inline(always)]
+    fn count(&self, _byte: u8) -> usize { 0 }
+    #[inline(always)]
+    fn scalar_fast(&self) -> bool { true }
+    #[inline(always)]
+    fn lookbehind(&self) -> usize { 0 }
+    #[inline]
+    fn describe(&self, _first: bool, _f: &mut fmt::Formatter<'_>) -> fmt::Result { Ok(()) }
+}
+
+impl<C: Condition, T: Tally> Tally for (C, T) {
+    const LEN: usize = 1 + T::LEN;
+    #[inline(always)]
+    unsafe fn tally<const SATURATE: bool>(&self, data: Vector, ctx: Context, acc: Vector) -> Vector {
+        // which bit of a lane `MoveMask` reads differs between backends, and conditions are free to
+        // return lanes with only that bit set, so any lane which is not zero is taken as met and
+        // reduced to one to subtract.
+        let met = arch::and(
+            arch::not(arch::eq(self.0.eval_context(data, ctx), arch::splat(0))), arch::splat(1)
+        );
+        let acc = if SATURATE { arch::saturating_sub(acc, met) } else { arch::sub(acc, met) };
+        self.1.tally::<SATURATE>(data, ctx, acc)
+    }
+    #[cfg(all(
+        feature = "std", feature = "simd", target_arch = "x86_64", target_feature = "sse2",
+        not(target_feature = "avx2")
+    ))]
+    #[inline(always)]
+    unsafe fn tally_avx2<const SATURATE: bool>(
+        &self, data: arch::avx2::Vector, acc: arch::avx2::Vector
+    ) -> arch::avx2::Vector {
+        use arch::avx2;
+        let met = avx2::and(
+            avx2::not(avx2::eq(self.0.eval_avx2(data), avx2::splat(0))), avx2::splat(1)
+        );
+        let acc = if SATURATE { avx2::saturating_sub(acc, met) } else { avx2::sub(acc, met) };
+        self.1.tally_avx2::<SATURATE>(data, acc)
+    }
+    #[inline(always)]
+    fn count(&self, byte: u8) -> usize { self.0.eval_byte(byte) as usize + self.1.count(byte) }
+    #[inline(always)]
+    fn scalar_fast(&self) -> bool { self.0.scalar_fast() && self.1.scalar_fast() }
+    #[inline(always)]
+    fn lookbehind(&self) -> usize { self.0.lookbehind().max(self.1.lookbehind()) }
+    #[inline]
+    fn describe(&self, first: bool, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !first { f.write_str(", ")?; }
+        self.0.describe(f)?;
+        self.1.describe(false, f)
+    }
+}
+
+/// Two lists of conditions counted as one, see [`Tally`]
+#[doc(hidden)]
+#[derive(Debug, Copy, Clone)]
+pub struct Group<A, B>(pub A, pub B);
+
+impl<A: Tally, B: Tally> Tally for Group<A, B> {
+    const LEN: usize = A::LEN + B::LEN;
+    #[inline(always)]
+    unsafe fn tally<const SATURATE: bool>(&self, data: Vector, ctx: Context, acc: Vector) -> Vector {
+        self.1.tally::<SATURATE>(data, ctx, self.0.tally::<SATURATE>(data, ctx, acc))
+    }
+    #[cfg(all(
+        feature = "std", feature = "simd", target_arch = "x86_64", target_feature = "sse2",
+        not(target_feature = "avx2")
+    ))]
+    #[inline(always)]
+    unsafe fn tally_avx2<const SATURATE: bool>(
+        &self, data: arch::avx2::Vector, acc: arch::avx2::Vector
+    ) -> arch::avx2::Vector {
+        self.1.tally_avx2::<SATURATE>(data, self.0.tally_avx2::<SATURATE>(data, acc))
+    }
+    #[inline(always)]
+    fn count(&self, byte: u8) -> usize { self.0.count(byte) + self.1.count(byte) }
+    #[inline(always)]
+    fn scalar_fast(&self) -> bool { self.0.scalar_fast() && self.1.scalar_fast() }
+    #[inline(always)]
+    fn lookbehind(&self) -> usize { self.0.lookbehind().max(self.1.lookbehind()) }
+    #[inline]
+    fn describe(&self, first: bool, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.describe(first, f)?;
+        self.1.describe(false, f)
+    }
+}
+
+macro_rules! counting_condition {
+    (
+        $(#[$meta:meta])* $name:ident, $words:literal,
+        |$count:ident, $k:ident| $byte:expr,
+        saturate: $saturate:literal,
+        |$ops:ident, $left:ident, $splat_k:ident| $vector:expr
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Copy, Clone)]
+        pub struct $name<T> {
+            k: u8,
+            conds: T
+        }
+
+        impl<T: Tally> $name<T> {
+            // each lane counts in a byte, so more conditions could wrap the count around
+            const VALID_LEN: () = assert!(T::LEN <= 255, "at most 255 conditions can be counted");
+
+            /// Count the `conds`, a list of nested pairs ending in `()`, against `k`
+            #[doc(hidden)]
+            #[inline(always)]
+            pub const fn new(k: u8, conds: T) -> Self {
+                let () = Self::VALID_LEN;
+                Self { k, conds }
+            }
+        }
+
+        impl<T: Tally> Condition for $name<T> {
+            #[inline(always)]
+            fn eval_vector(&self, data: Vector) -> Vector {
+                self.eval_context(data, Context::start())
+            }
+            #[inline(always)]
+            fn eval_byte(&self, byte: u8) -> bool {
+                let ($count, $k) = (self.conds.count(byte), self.k as usize);
+                $byte
+            }
+            #[inline(always)]
+            fn scalar_fast(&self) -> bool { self.conds.scalar_fast() }
+            #[inline]
+            fn describe(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, concat!($words, " {} of ("), self.k)?;
+                self.conds.describe(true, f)?;
+                f.write_str(")")
+            }
+            #[inline(always)]
+            fn eval_context(&self, data: Vector, ctx: Context) -> Vector {
+                use crate::arch as $ops;
+                unsafe {
+                    let $splat_k = arch::splat(self.k);
+                    let $left = self.conds.tally::<$saturate>(data, ctx, $splat_k);
+                    $vector
+                }
+            }
+            #[inline(always)]
+            fn lookbehind(&self) -> usize { self.conds.lookbehind() }
+            eval_avx2!(|self, data| {
+                use crate::arch::avx2 as $ops;
+                let $splat_k = avx2::splat(self.k);
+                let $left = self.conds.tally_avx2::<$saturate>(data, $splat_k);
+                $vector
+            });
+        }
+    };
+}
+
+counting_condition!(
+    /// Exactly `k` of the conditions hold, see [`exactly!`](crate::exactly)
+    Exactly, "exactly",
+    |count, k| count == k,
+    // counting down from `k` only reaches zero if exactly `k` conditions held, as there are fewer
+    // than 256 conditions it cannot wrap back around to zero.
+    saturate: false,
+    |ops, left, k| ops::eq(left, ops::splat(0))
+);
+counting_condition!(
+    /// At least `k` of the conditions hold, see [`at_least!`](crate::at_least)
+    AtLeast, "at least",
+    |count, k| count >= k,
+    // counting down from `k`, stopping at zero, reaches zero once `k` conditions held.
+    saturate: true,
+    |ops, left, k| ops::eq(left, ops::splat(0))
+);
+counting_condition!(
+    /// At most `k` of the conditions hold, see [`at_most!`](crate::at_most)
+    AtMost, "at most",
+    |count, k| count <= k,
+    // counting down from `k` leaves `k - count`, which exceeds `k` once it wraps below zero, as
+    // there are fewer than 256 conditions.
+    saturate: false,
+    |ops, left, k| ops::eq(ops::saturating_sub(left, k), ops::splat(0))
+);
+
+/// The condition holds for the byte `N` positions earlier, see [`prev`](crate::prev)
+#[derive(Debug, Copy, Clone)]
+pub struct Prev<const N: usize, C>(C);
+
+impl<const N: usize, C> Prev<N, C> {
+    // the bytes `N` back must come from within the preceding vector, of at least 16 bytes
+    const VALID_SHIFT: () = assert!(N > 0 && N < 16, "`prev` must look back between 1 and 15 bytes");
+
+    #[inline(always)]
+    pub(crate) const fn new(cond: C) -> Self {
+        let () = Self::VALID_SHIFT;
+        Self(cond)
+    }
+}
+
+impl<const N: usize, C: Condition> Condition for Prev<N, C> {
+    #[inline(always)]
+    fn eval_vector(&self, data: Vector) -> Vector {
+        self.eval_context(data, Context::start())
+    }
+    // a lone byte has nothing preceding it, so never meets the condition
+    #[inline(always)]
+    fn eval_byte(&self, _byte: u8) -> bool { false }
+    #[inline]
+    fn describe(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match N {
+            1 => f.write_str("after ")?,
+            _ => write!(f, "{N} bytes after ")?
+        }
+        describe_operand(&self.0, "after", f)
+    }
+    #[inline(always)]
+    fn eval_context(&self, data: Vector, ctx: Context) -> Vector {
+        unsafe {
+            let shifted = arch::shift_in::<N>(data, ctx.prev);
+            // the lanes with no byte `N` back, as they precede the input, never meet the condition
+            arch::and(self.0.eval_context(shifted, ctx.shift::<N>()), ctx.shifted_present::<N>())
+        }
+    }
+    #[inline(always)]
+    fn lookbehind(&self) -> usize { N + self.0.lookbehind() }
+}
+
+/// The conditions searched for by [`search_classify`](crate::search_classify), as a tuple of up to
+/// twelve conditions
+#[doc(hidden)]
+pub trait Classes {
+    /// Evaluate each lane of the `data`, setting the lanes meeting any of the conditions
+    fn eval_vector(&self, data: Vector) -> Vector;
+    /// Whether the `byte` meets any of the conditions
+    fn eval_byte(&self, byte: u8) -> bool;
+    /// Whether every condition is [`Condition::scalar_fast`]
+    fn scalar_fast(&self) -> bool;
+    /// Describe the conditions, joined by `or`
+    fn describe(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result;
+    /// Evaluate each lane of the `data`, where `ctx` holds the bytes preceding it, setting the
+    /// lanes meeting any of the conditions
+    fn eval_context(&self, data: Vector, ctx: Context) -> Vector;
+    /// The furthest [`Condition::lookbehind`] of the conditions
+    fn lookbehind(&self) -> usize;
+    /// Evaluate each lane of an AVX2 vector, setting the lanes meeting any of the conditions
+    ///
+    /// # Safety
+    ///
+    /// AVX2 must be available.
+    #[cfg(all(
+        feature = "std", feature = "simd", target_arch = "x86_64", target_feature = "sse2",
+        not(target_feature = "avx2")
+    ))]
+    unsafe fn eval_avx2(&self, data: arch::avx2::Vector) -> arch::avx2::Vector;
+    /// The index of the first condition met by the `byte`, `at` loads the vector starting at the
+    /// byte along with its context, for the conditions which look behind
+    fn class(&self, byte: u8, at: impl Fn() -> (Vector, Context)) -> Option<usize>;
+}
+
+/// Whether the `cond` holds for the `byte`, evaluating the first lane of the vector `at` gives if
+/// the condition looks behind
+#[inline(always)]
+fn met<C: Condition>(cond: &C, byte: u8, at: &impl Fn() -> (Vector, Context)) -> bool {
+    if cond.lookbehind() == 0 {
+        cond.eval_byte(byte)
+    } else {
+        let (data, ctx) = at();
+        unsafe { MoveMask::new(cond.eval_context(data, ctx)).trailing_zeros() == 0 }
+    }
+}
+
+macro_rules! classes {
+    ($(($first:tt $head:ident $(, $idx:tt $ty:ident)*))+) => {$(
+        impl<$head: Condition $(, $ty: Condition)*> Classes for ($head, $($ty,)*) {
+            #[inline(always)]
+            fn eval_vector(&self, data: Vector) -> Vector {
+                let acc = self.$first.eval_vector(data);
+                $( let acc = unsafe { arch::or(acc, self.$idx.eval_vector(data)) }; )*
+                acc
+            }
+            #[inline(always)]
+            fn eval_byte(&self, byte: u8) -> bool {
+                self.$first.eval_byte(byte) $(|| self.$idx.eval_byte(byte))*
+            }
+            #[inline(always)]
+            fn scalar_fast(&self) -> bool {
+                self.$first.scalar_fast() $(&& self.$idx.scalar_fast())*
+            }
+            #[inline]
+            fn describe(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                describe_operand(&self.$first, "or", f)?;
+                $(
+                    f.write_str(" or ")?;
+                    describe_operand(&self.$idx, "or", f)?;
+                )*
+                Ok(())
+            }
+            #[inline(always)]
+            fn eval_context(&self, data: Vector, ctx: Context) -> Vector {
+                let acc = self.$first.eval_context(data, ctx);
+                $( let acc = unsafe { arch::or(acc, self.$idx.eval_context(data, ctx)) }; )*
+                acc
+            }
+            #[inline(always)]
+            fn lookbehind(&self) -> usize {
+                self.$first.lookbehind() $(.max(self.$idx.lookbehind()))*
+            }
+            #[cfg(all(
+                feature = "std", feature = "simd", target_arch = "x86_64", target_feature = "sse2",
+                not(target_feature = "avx2")
+            ))]
+            #[inline(always)]
+            unsafe fn eval_avx2(&self, data: arch::avx2::Vector) -> arch::avx2::Vector {
+                let acc = self.$first.eval_avx2(data);
+                $( let acc = arch::avx2::or(acc, self.$idx.eval_avx2(data)); )*
+                acc
+            }
+            #[inline]
+            fn class(&self, byte: u8, at: impl Fn() -> (Vector, Context)) -> Option<usize> {
+                if met(&self.$first, byte, &at) { return Some($first); }
+                $( if met(&self.$idx, byte, &at) { return Some($idx); } )*
+                None
+            }
+        }
+    )+};
+}
+
+classes!(
+    (0 A)
+    (0 A, 1 B)
+    (0 A, 1 B, 2 C)
+    (0 A, 1 B, 2 C, 3 D)
+    (0 A, 1 B, 2 C, 3 D, 4 E)
+    (0 A, 1 B, 2 C, 3 D, 4 E, 5 F)
+    (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G)
+    (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H)
+    (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H, 8 I)
+    (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H, 8 I, 9 J)
+    (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H, 8 I, 9 J, 10 K)
+    (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H, 8 I, 9 J, 10 K, 11 L)
+);
+
+/// Any of the [`Classes`] holds, the condition [`search_classify`](crate::search_classify) scans for
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct AnyOf<'c, T>(pub(crate) &'c T);
+
+impl<'c, T: Classes> Condition for AnyOf<'c, T> {
+    #[inline(always)]
+    fn eval_vector(&self, data: Vector) -> Vector { self.0.eval_vector(data) }
+    #[inline(always)]
+    fn eval_byte(&self, byte: u8) -> bool { self.0.eval_byte(byte) }
+    #[inline(always)]
+    fn scalar_fast(&self) -> bool { self.0.scalar_fast() }
+    #[inline]
+    fn describe(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { self.0.describe(f) }
+    #[inline(always)]
+    fn connective(&self) -> Option<&'static str> { Some("or") }
+    #[inline(always)]
+    fn eval_context(&self, data: Vector, ctx: Context) -> Vector { self.0.eval_context(data, ctx) }
+    #[inline(always)]
+    fn lookbehind(&self) -> usize { self.0.lookbehind() }
+    eval_avx2!(|self, data| self.0.eval_avx2(data));
+}
+
+/// The conditions consecutive bytes meet, searched for by [`search_seq`](crate::search_seq), as a
+/// tuple of up to sixteen conditions
+#[doc(hidden)]
+pub trait Sequence {
+    /// The number of conditions
+    const LEN: usize;
+    /// The condition met by the last byte of the sequence
+    type Cond: Condition;
+    /// The condition met by the last byte of the sequence, each condition before it looking behind
+    /// by its distance from the end with [`Prev`]
+    fn into_cond(self) -> Self::Cond;
+}
+
+impl<A: Condition> Sequence for (A,) {
+    const LEN: usize = 1;
+    type Cond = A;
+    #[inline(always)]
+    fn into_cond(self) -> Self::Cond { self.0 }
+}
+
+macro_rules! sequence {
+    ($($len:literal: ($($idx:tt $ty:ident),+) $last_idx:tt $last:ident;)+) => {$(
+        impl<$($ty: Condition,)+ $last: Condition> Sequence for ($($ty,)+ $last) {
+            const LEN: usize = $len;
+            type Cond = And<Prev<1, <($($ty,)+) as Sequence>::Cond>, $last>;
+            #[inline(always)]
+            fn into_cond(self) -> Self::Cond {
+                And::new(Prev::new(($(self.$idx,)+).into_cond()), self.$last_idx)
+            }
+        }
+    )+};
+}
+
+sequence!(
+    2: (0 A) 1 B;
+    3: (0 A, 1 B) 2 C;
+    4: (0 A, 1 B, 2 C) 3 D;
+    5: (0 A, 1 B, 2 C, 3 D) 4 E;
+    6: (0 A, 1 B, 2 C, 3 D, 4 E) 5 F;
+    7: (0 A, 1 B, 2 C, 3 D, 4 E, 5 F) 6 G;
+    8: (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G) 7 H;
+    9: (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H) 8 I;
+    10: (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H, 8 I) 9 J;
+    11: (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H, 8 I, 9 J) 10 K;
+    12: (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H, 8 I, 9 J, 10 K) 11 L;
+    13: (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H, 8 I, 9 J, 10 K, 11 L) 12 M;
+    14: (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H, 8 I, 9 J, 10 K, 11 L, 12 M) 13 N;
+    15: (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H, 8 I, 9 J, 10 K, 11 L, 12 M, 13 N) 14 O;
+    16: (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H, 8 I, 9 J, 10 K, 11 L, 12 M, 13 N, 14 O) 15 P;
+);
+
+/// A condition which can be composed with the `|`, `&`, `^` and `!` operators
+///
+/// The conditions built by [`eq`], [`range!`], [`cond!`] and the combinators, as well as
+/// [`Nibbles`] and [`ByteClass`], already support the operators. `Cond` adds them to any other
+/// condition, such as a closure. The operators build exactly what [`or`], [`and`], [`xor`]
+/// and [`not`] would, so `a | b` compiles to the same code as `or(a, b)`.
+///
+/// Operators cannot be used in `const` position, there the methods of the same name build the
+/// same condition for conditions which are `Copy`, or the free functions can be wrapped, as in
+/// `Cond(or(a, b))`.
+///
+/// # Example
+///
+/// ```
+/// use swift_check::{Cond, Condition, eq, range, for_all_ensure};
+/// use swift_check::arch::{self, Vector};
+///
+/// let cond = (eq(b'a') | eq(b'b')) & !eq(b'c');
+/// assert!(for_all_ensure(b"abba", cond));
+///
+/// // closures gain the operators once wrapped
+/// let upper = Cond(|data: Vector| unsafe { arch::eq(arch::and(data, arch::splat(0xE0)), arch::splat(0x40)) });
+/// assert!(for_all_ensure(b"HELLO_WORLD", upper & !eq(b'@') | eq(b'_')));
+///
+/// // and in const position
+/// const fn hex() -> impl Condition {
+///     Cond(range!(b'0'..=b'9')).or(range!(b'a'..=b'f')).and(Cond(eq(b'0')).not())
+/// }
+/// assert!(for_all_ensure(b"deadbeef", hex()));
+/// ```
+///
+/// [`eq`]: crate::eq
+/// [`range!`]: crate::range
+/// [`cond!`]: crate::cond
+/// [`Nibbles`]: crate::Nibbles
+/// [`ByteClass`]: crate::ByteClass
+/// [`or`]: crate::or
+/// [`and`]: crate::and
+/// [`xor`]: crate::xor
+/// [`not`]: crate::not
+#[derive(Debug, Copy, Clone)]
+#[repr(transparent)]
+pub struct Cond<F>(pub F);
+
+impl<F: Condition + Copy> Cond<F> {
+    /// Either condition holds, the `const` equivalent of `self | rhs`
+    #[inline(always)]
+    pub const fn or<B: Condition>(self, rhs: B) -> Cond<Or<F, B>> {
+        Cond(Or::new(self.0, rhs))
+    }
+
+    /// Both conditions hold, the `const` equivalent of `self & rhs`
+    #[inline(always)]
+    pub const fn and<B: Condition>(self, rhs: B) -> Cond<And<F, B>> {
+        Cond(And::new(self.0, rhs))
+    }
+
+    /// Exactly one of the conditions holds, the `const` equivalent of `self ^ rhs`
+    #[inline(always)]
+    pub const fn xor<B: Condition>(self, rhs: B) -> Cond<Xor<F, B>> {
+        Cond(Xor::new(self.0, rhs))
+    }
+
+    /// The condition does not hold, the `const` equivalent of `!self`
+    #[inline(always)]
+    #[allow(clippy::should_implement_trait)] // `Not` is implemented, this is usable in `const`
+    pub const fn not(self) -> Cond<Not<F>> {
+        Cond(Not::new(self.0))
+    }
+}
+
+impl<F: Condition> Condition for Cond<F> {
+    #[inline(always)]
+    fn eval_vector(&self, data: Vector) -> Vector { self.0.eval_vector(data) }
+    #[inline(always)]
+    fn eval_byte(&self, byte: u8) -> bool { self.0.eval_byte(byte) }
+    #[inline(always)]
+    fn scalar_fast(&self) -> bool { self.0.scalar_fast() }
+    #[inline]
+    fn describe(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { self.0.describe(f) }
+    #[inline(always)]
+    fn connective(&self) -> Option<&'static str> { self.0.connective() }
+    #[inline(always)]
+    fn eval_context(&self, data: Vector, ctx: Context) -> Vector { self.0.eval_context(data, ctx) }
+    #[inline(always)]
+    fn lookbehind(&self) -> usize { self.0.lookbehind() }
+    eval_avx2!(|self, data| self.0.eval_avx2(data));
+}
+
+/// Implement the operators for conditions, each producing a [`Cond`] so the result composes further
+macro_rules! impl_operators {
+    ($([$($gen:tt)*] $ty:ty),+ $(,)?) => {$(
+        impl<Rhs: Condition, $($gen)*> core::ops::BitOr<Rhs> for $ty {
+            type Output = Cond<Or<Self, Rhs>>;
+            #[inline(always)]
+            fn bitor(self, rhs: Rhs) -> Self::Output { Cond(Or::new(self, rhs)) }
+        }
+
+        impl<Rhs: Condition, $($gen)*> core::ops::BitAnd<Rhs> for $ty {
+            type Output = Cond<And<Self, Rhs>>;
+            #[inline(always)]
+            fn bitand(self, rhs: Rhs) -> Self::Output { Cond(And::new(self, rhs)) }
+        }
+
+        impl<Rhs: Condition, $($gen)*> core::ops::BitXor<Rhs> for $ty {
+            type Output = Cond<Xor<Self, Rhs>>;
+            #[inline(always)]
+            fn bitxor(self, rhs: Rhs) -> Self::Output { Cond(Xor::new(self, rhs)) }
+        }
+
+        impl<$($gen)*> core::ops::Not for $ty {
+            type Output = Cond<Not<Self>>;
+            #[inline(always)]
+            fn not(self) -> Self::Output { Cond(Not::new(self)) }
+        }
+    )+};
+}
+
+impl_operators!(
+    [] Eq,
+    [] MaskedEq,
+    [] AnyBits,
+    [F] Range<F>,
+    [C] Not<C>,
+    [A, B] And<A, B>,
+    [A, B] Or<A, B>,
+    [A, B] Xor<A, B>,
+    [T] Exactly<T>,
+    [T] AtLeast<T>,
+    [T] AtMost<T>,
+    [C] Named<C>,
+    [const N: usize, C] Prev<N, C>,
+    [] crate::Nibbles,
+    [] crate::ByteClass
+);
+
+impl<F: Fn(Vector) -> Vector> NoLookbehind for F {}
+impl<'c, C: NoLookbehind + ?Sized> NoLookbehind for ByRef<'c, C> {}
+impl<C: NoLookbehind> NoLookbehind for Named<C> {}
+impl NoLookbehind for Eq {}
+impl NoLookbehind for MaskedEq {}
+impl NoLookbehind for AnyBits {}
+impl<F> NoLookbehind for Range<F> {}
+impl<C: NoLookbehind> NoLookbehind for Not<C> {}
+impl<A: NoLookbehind, B: NoLookbehind> NoLookbehind for And<A, B> {}
+impl<A: NoLookbehind, B: NoLookbehind> NoLookbehind for Or<A, B> {}
+impl<A: NoLookbehind, B: NoLookbehind> NoLookbehind for Xor<A, B> {}
+// the lists of counted conditions, see `Tally`
+impl NoLookbehind for () {}
+impl<C: NoLookbehind, T: NoLookbehind> NoLookbehind for (C, T) {}
+impl<A: NoLookbehind, B: NoLookbehind> NoLookbehind for Group<A, B> {}
+impl<T: NoLookbehind> NoLookbehind for Exactly<T> {}
+impl<T: NoLookbehind> NoLookbehind for AtLeast<T> {}
+impl<T: NoLookbehind> NoLookbehind for AtMost<T> {}
+impl<F: NoLookbehind> NoLookbehind for Cond<F> {}
+impl NoLookbehind for crate::Nibbles {}
+impl NoLookbehind for crate::ByteClass {}
+
+// `Cond` unwraps itself rather than nesting, keeping the types, and so descriptions, flat
+impl<F, Rhs: Condition> core::ops::BitOr<Rhs> for Cond<F> {
+    type Output = Cond<Or<F, Rhs>>;
+    #[inline(always)]
+    fn bitor(self, rhs: Rhs) -> Self::Output { Cond(Or::new(self.0, rhs)) }
+}
+
+impl<F, Rhs: Condition> core::ops::BitAnd<Rhs> for Cond<F> {
+    type Output = Cond<And<F, Rhs>>;
+    #[inline(always)]
+    fn bitand(self, rhs: Rhs) -> Self::Output { Cond(And::new(self.0, rhs)) }
+}
+
+impl<F, Rhs: Condition> core::ops::BitXor<Rhs> for Cond<F> {
+    type Output = Cond<Xor<F, Rhs>>;
+    #[inline(always)]
+    fn bitxor(self, rhs: Rhs) -> Self::Output { Cond(Xor::new(self.0, rhs)) }
+}
+
+impl<F> core::ops::Not for Cond<F> {
+    type Output = Cond<Not<F>>;
+    #[inline(always)]
+    fn not(self) -> Self::Output { Cond(Not::new(self.0)) }
+}
+
+/// Check that each byte matches a pattern, written as in [`matches!`]
+///
+/// Each branch of the pattern, separated by `|`, is a byte literal, an inclusive range `a..=b` or
+/// an exclusive range `a..b`, with the same meaning as in a `match`. The whole pattern may be
+/// negated with `!`, and wrapped in parentheses.
+///
+/// Up to three branches are checked one by one, as with [`any!`], [`range!`] and [`eq`], otherwise
+/// the set is compiled into a [`ByteClass`], as with [`byte_set!`]. That is a nibble lookup costing
+/// the same however many branches there are, where the backend has a native shuffle, and a
+/// comparison for each run of bytes otherwise.
+///
+/// The pattern is checked as a `match` would check it, so empty or reversed ranges are rejected
+/// however many branches there are.
+///
+/// As every condition also evaluates a single byte through [`Condition::eval_byte`], there is no
+/// need to keep a separate `matches!` for scalar code.
+///
+/// # Example
+///
+/// ```
+/// use swift_check::{cond, for_all_ensure, search, Condition};
+///
+/// let ident = cond!(b'a'..=b'z' | b'A'..=b'Z' | b'_' | b'0'..=b'9');
+/// assert!(for_all_ensure(b"snake_case_2", ident));
+/// assert!(ident.eval_byte(b'Q'));
+///
+/// let not_quote = cond!(!(b'"' | b'\\'));
+/// assert_eq!(search(br#"escaped \"quote\""#, not_quote), Some(0));
+///
+/// // exclusive ranges exclude only the upper bound, as in a `match`
+/// let octal = cond!(b'0'..b'8');
+/// assert!(octal.eval_byte(b'0') && !octal.eval_byte(b'8'));
+/// ```
+///
+/// ```compile_fail
+/// // an empty range
+/// let never = swift_check::cond!(b'a'..b'a' | b'x');
+/// ```
+///
+/// [`any!`]: crate::any
+/// [`range!`]: crate::range
+/// [`eq`]: crate::eq
+/// [`byte_set!`]: crate::byte_set
+/// [`ByteClass`]: crate::ByteClass
+#[macro_export]
+macro_rules! cond {
+    (! $($pattern:tt)+) => {
+        $crate::not($crate::cond!($($pattern)+))
+    };
+    (($($pattern:tt)+)) => {
+        $crate::cond!($($pattern)+)
+    };
+    ($($pattern:tt)+) => {
+        // reject empty and reversed ranges as a `match` does, few branches never reach one
+        match 0u8 {
+            // a pattern covering every byte leaves the wildcard unreachable
+            #[allow(unreachable_patterns)]
+            $($pattern)+ | _ => $crate::__cond!([] [$($pattern)+] $($pattern)+)
+        }
+    };
+}
+
+/// Turn each branch of the pattern into a condition, then combine them
+#[doc(hidden)] #[macro_export]
+macro_rules! __cond {
+    ([$($cond:expr),*] [$($pattern:tt)+] $lo:literal ..= $hi:literal $(| $($rest:tt)+)?) => {
+        $crate::__cond!([$($cond,)* $crate::range!($lo..=$hi)] [$($pattern)+] $($($rest)+)?)
+    };
+    ([$($cond:expr),*] [$($pattern:tt)+] $lo:literal .. $hi:literal $(| $($rest:tt)+)?) => {
+        $crate::__cond!(
+            [$($cond,)* $crate::condition::Range::new(
+                $lo, $hi as u16 - $lo as u16, $crate::arch::range::<$lo, { $hi - 1 }>()
+            )]
+            [$($pattern)+] $($($rest)+)?
+        )
+    };
+    ([$($cond:expr),*] [$($pattern:tt)+] $byte:literal $(| $($rest:tt)+)?) => {
+        $crate::__cond!([$($cond,)* $crate::eq($byte)] [$($pattern)+] $($($rest)+)?)
+    };
+    // few branches are cheaper to check one by one than to look up
+    ([$a:expr] [$($pattern:tt)+]) => {
+        $a
+    };
+    ([$a:expr, $b:expr] [$($pattern:tt)+]) => {
+        $crate::any!($a, $b)
+    };
+    ([$a:expr, $b:expr, $c:expr] [$($pattern:tt)+]) => {
+        $crate::any!($a, $b, $c)
+    };
+    ([$($cond:expr),+] [$($pattern:tt)+]) => {
+        $crate::__byte_class!($($pattern)+)
+    };
+}
+
+/// Evaluate the condition for every byte value
+///
+/// Each of the 256 bytes is loaded, a vector at a time, and the condition evaluated over them, so
+/// this reflects exactly what the scans see. Use it to test a character class, compare two
+/// conditions for equivalence, or with [`ByteSet::from_cond`] print a class for review.
+///
+/// # Example
+///
+/// ```
+/// use swift_check::{truth_table, any, all, eq, not, range};
+///
+/// let digit = truth_table(range!(b'0'..=b'9'));
+/// assert!(digit[b'7' as usize]);
+/// assert_eq!(digit.iter().filter(|member| **member).count(), 10);
+///
+/// // two spellings of the same class
+/// assert_eq!(
+///     truth_table(any!(range!(b'a'..=b'z'), range!(b'A'..=b'Z'))),
+///     truth_table(all!(range!(b'A'..=b'z'), not(range!(b'['..=b'`'))))
+/// );
+/// ```
+///
+/// [`ByteSet::from_cond`]: crate::ByteSet::from_cond
+#[must_use]
+pub fn truth_table(cond: impl Condition) -> [bool; 256] {
+    let mut bytes = [0u8; 256];
+    for (byte, value) in bytes.iter_mut().zip(0..=255) { *byte = value; }
+
+    let mut table = [false; 256];
+    for offset in (0..256).step_by(arch::WIDTH) {
+        // SAFETY: 256 is a multiple of `arch::WIDTH`, so the load never passes the end
+        let mut mask = unsafe { MoveMask::new(cond.eval_vector(arch::load_at(&bytes, offset))) };
+        while mask.any_bit_set() {
+            table[offset + mask.trailing_zeros() as usize] = true;
+            mask = mask.clear_lowest();
+        }
+    }
+    table
+}
+
+#[cfg(all(test, not(mirai)))]
+mod tests {
+    use super::*;
+    use crate::{eq, not, and, or, xor, range, any, all, one_of, exactly, at_least, at_most, ByteSet};
+    use quickcheck::quickcheck;
+
+    /// The byte evaluation must agree with the vector evaluation of the same byte
+    fn agrees(cond: impl Condition, byte: u8) -> bool {
+        let vector = unsafe { MoveMask::new(cond.eval_vector(arch::splat(byte))).all_bits_set() };
+        vector == cond.eval_byte(byte)
+    }
+
+    #[test]
+    fn byte_matches_vector() {
+        for byte in 0..=255u8 {
+            assert!(agrees(eq(b'a'), byte));
+            assert!(agrees(not(eq(b'a')), byte));
+            assert!(agrees(range!(b'a'..=b'z'), byte));
+            assert!(agrees(range!(0..=255), byte));
+            assert!(agrees(range!(b'a'..b'z'), byte));
+            assert!(agrees(range!(5..5), byte));
+            assert!(agrees(range!(< 128), byte));
+            assert!(agrees(range!(<= 128), byte));
+            assert!(agrees(range!(> 128), byte));
+            assert!(agrees(range!(> 255), byte));
+            assert!(agrees(range!(>= 128), byte));
+            assert!(agrees(range!(>= 0), byte));
+            assert!(agrees(and(range!(b'0'..=b'9'), not(eq(b'5'))), byte));
+            assert!(agrees(or(eq(b'1'), eq(b'3')), byte));
+            assert!(agrees(xor(range!(b'0'..=b'5'), range!(b'5'..=b'9')), byte));
+            assert!(agrees(any!(eq(b' '), eq(b'\t'), eq(b'\n'), eq(b'\r')), byte));
+            assert!(agrees(all!(range!(>= 32), range!(< 127), not(eq(b'"'))), byte));
+            assert!(agrees(one_of!(range!(b'0'..=b'9'), range!(b'5'..=b'z'), eq(b'7')), byte));
+            assert!(agrees(one_of!(range!(< 64), range!(< 128), range!(< 192), eq(b'a')), byte));
+            assert!(agrees(exactly!(2; range!(< 64), range!(< 128), range!(< 192), eq(b'a'), eq(0)), byte));
+            assert!(agrees(at_least!(3; range!(< 64), range!(< 128), range!(< 192), eq(b'a'), eq(0)), byte));
+            assert!(agrees(at_most!(1; range!(< 64), range!(< 128), range!(< 192), eq(b'a'), eq(0)), byte));
+            assert!(agrees(at_most!(7; range!(< 64), range!(< 128)), byte));
+            assert!(agrees(crate::masked_eq(0xC0, 0x80), byte));
+            assert!(agrees(crate::any_bits(0x81), byte));
+            assert!(agrees(crate::all_bits(0x81), byte));
+            assert!(agrees(crate::high_bit_set(), byte));
+            assert!(agrees(|data| unsafe { arch::eq(data, arch::splat(b'x')) }, byte));
+        }
+    }
+
+    quickcheck! {
+        fn scalar_fast_follows_parts(a: u8, b: u8) -> bool {
+            let closure = |data| unsafe { arch::eq(data, arch::splat(a)) };
+            and(eq(a), range!(b'a'..=b'z')).scalar_fast()
+                && !or(eq(a), closure).scalar_fast()
+                && not(eq(b)).by_ref().scalar_fast()
+        }
+        fn bit_patterns_match_scalar(mask: u8, value: u8) -> bool {
+            let (masked, any, all) = (
+                truth_table(crate::masked_eq(mask, value)),
+                truth_table(crate::any_bits(mask)),
+                truth_table(crate::all_bits(mask))
+            );
+            let high = truth_table(crate::high_bit_set());
+            (0..=255u8).all(|byte| {
+                high[byte as usize] == (byte >= 0x80)
+                    && masked[byte as usize] == (byte & mask == value)
+                    && any[byte as usize] == (byte & mask != 0)
+                    && all[byte as usize] == (byte & mask == mask)
+            })
+        }
+    }
+
+    #[test]
+    fn truth_table_matches_byte() {
+        let cond = any!(range!(b'0'..=b'9'), eq(b'_'), range!(> 200));
+        let table = truth_table(cond);
+        assert!((0..=255u8).all(|byte| table[byte as usize] == cond.eval_byte(byte)));
+        assert_eq!(ByteSet::from_cond(cond), ByteSet::from(table));
+    }
+
+    fn table_where(pred: impl Fn(u8) -> bool) -> [bool; 256] {
+        let mut table = [false; 256];
+        for byte in 0..=255u8 { table[byte as usize] = pred(byte); }
+        table
+    }
+
+    macro_rules! matches_pattern {
+        ($($pattern:tt)+) => {
+            assert_eq!(
+                truth_table(crate::cond!($($pattern)+)),
+                table_where(|byte| matches!(byte, $($pattern)+)),
+                "{}", stringify!($($pattern)+)
+            );
+        };
+    }
+
+    #[test]
+    fn cond_matches_pattern() {
+        matches_pattern!(b'a');
+        matches_pattern!(b'a'..=b'z');
+        matches_pattern!(b'0'..b'8');
+        matches_pattern!(0..1);
+        matches_pattern!(0..=255);
+        matches_pattern!(200..=255 | 0..10);
+        matches_pattern!(b'a'..=b'z' | b'A'..=b'Z' | b'_');
+        matches_pattern!(b'a'..=b'z' | b'A'..=b'Z' | b'_' | b'0'..=b'9');
+        matches_pattern!(b' ' | b'\t' | b'\r' | b'\n' | 0x0B | 0x0C);
+        matches_pattern!(b'A'..b'[' | b'a'..b'{' | b'0'..b':' | b'+' | b'/' | b'=');
+    }
+
+    #[test]
+    fn cond_negates() {
+        let table = truth_table(crate::cond!(!(b'"' | b'\\')));
+        assert!((0..=255u8).all(|byte| table[byte as usize] == !matches!(byte, b'"' | b'\\')));
+        let table = truth_table(crate::cond!(!b'a'..=b'z' | b'_' | b'-' | b'.'));
+        assert!((0..=255u8).all(|byte| table[byte as usize] == !matches!(byte, b'a'..=b'z' | b'_' | b'-' | b'.')));
+    }
+
+    #[test]
+    fn describes_composition() {
+        let letter = crate::named("letter", any!(range!(b'a'..=b'z'), range!(b'A'..=b'Z')));
+        let ident = any!(letter, range!(b'0'..=b'9'), eq(b'_'));
+        assert_eq!(ident.description().to_string(), "letter or '0'..='9' or '_'");
+
+        let cond = and(not(or(eq(b'\n'), eq(0))), xor(range!(0..=127), eq(b'\'')));
+        assert_eq!(cond.description().to_string(), r"not (0x0a or 0x00) and (0x00..=0x7f xor '\'')");
+        assert_eq!(one_of!(eq(b'a'), eq(b'b'), letter).description().to_string(), "exactly 1 of ('a', 'b', letter)");
+        assert_eq!(
+            crate::at_least!(2; eq(b'0'), eq(b'1'), eq(b'2'), eq(b'3'), eq(b'4'), eq(b'5'), eq(b'6'), eq(b'7'), eq(b'8'))
+                .description().to_string(),
+            "at least 2 of ('0', '1', '2', '3', '4', '5', '6', '7', '8')"
+        );
+        assert_eq!(range!(b'a'..b'b').description().to_string(), "nothing");
+        assert_eq!(range!(0..=255).description().to_string(), "any byte");
+        assert_eq!((|data| data).description().to_string(), "condition");
+    }
+
+    #[test]
+    fn describes_prev() {
+        let escaped = and(eq(b'"'), crate::prev::<1, _>(eq(b'\\')));
+        assert_eq!(escaped.description().to_string(), r#"'\"' and after '\\'"#);
+        assert_eq!(
+            not(crate::prev::<2, _>(or(eq(b'a'), eq(b'\r')))).description().to_string(),
+            "not 2 bytes after ('a' or 0x0d)"
+        );
+        assert_eq!(crate::prev::<1, _>(eq(b'a')).lookbehind(), 1);
+        assert_eq!(and(crate::prev::<3, _>(crate::prev::<2, _>(eq(b'a'))), eq(b'b')).lookbehind(), 5);
+        // a lone byte has nothing before it
+        assert!(!crate::prev::<1, _>(eq(b'a')).eval_byte(b'a'));
+        assert!(not(crate::prev::<1, _>(eq(b'a'))).eval_byte(b'a'));
+    }
+
+    #[test]
+    fn operators_match_functions() {
+        let digit = range!(b'0'..=b'9');
+        assert_eq!(
+            truth_table((eq(b'a') | eq(b'b')) & !eq(b'c') ^ digit),
+            truth_table(xor(and(or(eq(b'a'), eq(b'b')), not(eq(b'c'))), digit))
+        );
+        let closure = Cond(|data| unsafe { arch::eq(data, arch::splat(b'x')) });
+        assert_eq!(truth_table(!closure | digit), truth_table(or(not(eq(b'x')), digit)));
+        assert_eq!(truth_table(closure.or(digit).not()), truth_table(not(or(eq(b'x'), digit))));
+
+        let vowels = crate::Nibbles::new(&truth_table(crate::cond!(b'a' | b'e' | b'i' | b'o' | b'u')));
+        assert_eq!((vowels | eq(b'y')).description().to_string(), "[aeiou] or 'y'");
+    }
+}
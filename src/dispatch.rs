@@ -0,0 +1,312 @@
+//! Runtime selection of the implementation
+//!
+//! The backend is normally fixed when the crate is compiled, by the target features enabled. With
+//! the `std` feature, builds for baseline `x86_64` (SSE2 only) additionally detect AVX2 the first
+//! time it is needed, and route the scans listed on [`backend`] through the 32 byte AVX2 scans,
+//! compiled with AVX2 enabled.
+
+/// The implementation backing the scans
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Backend {
+    /// Portable byte by byte implementation, used when no SIMD backend is available
+    Fallback,
+    /// `x86_64` SSE2, 16 bytes at a time
+    Sse2,
+    /// `x86_64` AVX2, 32 bytes at a time
+    Avx2,
+    /// `aarch64` NEON, 16 bytes at a time
+    Neon,
+    /// WebAssembly `simd128`, 16 bytes at a time
+    Simd128
+}
+
+impl Backend {
+    /// The name of the backend, as written in its target feature
+    #[inline] #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Fallback => "fallback",
+            Self::Sse2 => "sse2",
+            Self::Avx2 => "avx2",
+            Self::Neon => "neon",
+            Self::Simd128 => "simd128"
+        }
+    }
+
+    /// The number of bytes the backend scans at a time
+    #[inline] #[must_use]
+    pub const fn width(self) -> usize {
+        match self {
+            Self::Fallback => 1,
+            Self::Avx2 => 32,
+            Self::Sse2 | Self::Neon | Self::Simd128 => 16
+        }
+    }
+}
+
+impl core::fmt::Display for Backend {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+cfg_sse!( const COMPILED: Backend = Backend::Sse2; );
+cfg_avx2!( const COMPILED: Backend = Backend::Avx2; );
+cfg_neon!( const COMPILED: Backend = Backend::Neon; );
+cfg_simd128!( const COMPILED: Backend = Backend::Simd128; );
+cfg_fallback!( const COMPILED: Backend = Backend::Fallback; );
+
+/// Which implementation is active
+///
+/// This is decided at compile time, other than for builds for baseline `x86_64` with the `std`
+/// feature, which report [`Backend::Avx2`] once AVX2 has been detected. Detection happens once,
+/// so the backend reported does not change while running.
+///
+/// The backend reported is the one used by the dispatched scans: [`search`], [`search_classify`],
+/// [`rsearch`], [`count`], [`validate`], [`validate_expecting`], [`span`], the [`trim`] functions,
+/// [`for_all_ensure`], [`for_all_ensure_ct`] and [`require::check`]. Where AVX2 is detected these
+/// still scan with SSE2:
+///
+/// - Inputs shorter than the AVX2 vector, and conditions which look behind, such as [`prev`],
+///   which includes [`search_seq`].
+/// - The iterators, [`search_all`] and the [`split`], [`splitn`] and [`split_inclusive`] iterators.
+/// - The [`StreamScanner`], and so the [`io`] adapters built on it.
+/// - Substring search, [`Finder`] and [`find_substring`].
+/// - [`ensure`] and [`find`], which are given a single vector.
+///
+/// # Example
+///
+/// ```
+/// use swift_check::{backend, Backend};
+///
+/// let active = backend();
+/// assert_eq!(backend(), active);
+/// assert!(active == Backend::Fallback || active.width() >= 16);
+/// println!("scanning {} bytes at a time with {active}", active.width());
+/// ```
+///
+/// [`search`]: crate::search
+/// [`search_classify`]: crate::search_classify
+/// [`rsearch`]: crate::rsearch
+/// [`count`]: crate::count
+/// [`validate`]: crate::validate
+/// [`validate_expecting`]: crate::validate_expecting
+/// [`span`]: crate::span
+/// [`trim`]: crate::trim
+/// [`for_all_ensure`]: crate::for_all_ensure
+/// [`for_all_ensure_ct`]: crate::for_all_ensure_ct
+/// [`require::check`]: crate::require::check
+/// [`prev`]: crate::prev
+/// [`search_seq`]: crate::search_seq
+/// [`search_all`]: crate::search_all
+/// [`split`]: crate::split
+/// [`splitn`]: crate::splitn
+/// [`split_inclusive`]: crate::split_inclusive
+/// [`StreamScanner`]: crate::StreamScanner
+/// [`io`]: crate::io
+/// [`Finder`]: crate::Finder
+/// [`find_substring`]: crate::find_substring
+/// [`ensure`]: crate::ensure
+/// [`find`]: crate::find
+#[inline]
+#[must_use]
+pub fn backend() -> Backend {
+    #[cfg(all(
+        feature = "std", feature = "simd", target_arch = "x86_64",
+        target_feature = "sse2", not(target_feature = "avx2")
+    ))]
+    if has_avx2() { return Backend::Avx2; }
+    COMPILED
+}
+
+cfg_static!(
+    /// Run the scan `$scan` from the backend selected at compile time
+    macro_rules! dispatch {
+        ($scan:ident($($arg:expr),* $(,)?)) => {
+            $crate::arch::scan::$scan($($arg),*)
+        };
+    }
+);
+
+cfg_dispatch!(
+    use core::sync::atomic::{AtomicU8, Ordering};
+
+    const UNKNOWN: u8 = 0;
+    const BASELINE: u8 = 1;
+    const AVX2: u8 = 2;
+
+    static DETECTED: AtomicU8 = AtomicU8::new(UNKNOWN);
+
+    /// Whether AVX2 is available, detecting it on the first call
+    #[inline(always)]
+    pub(crate) fn has_avx2() -> bool {
+        match DETECTED.load(Ordering::Relaxed) {
+            UNKNOWN => detect(),
+            found => found == AVX2
+        }
+    }
+
+    #[cold]
+    fn detect() -> bool {
+        let found = std::is_x86_feature_detected!("avx2");
+        // racing detections all store the same answer
+        DETECTED.store(if found { AVX2 } else { BASELINE }, Ordering::Relaxed);
+        found
+    }
+
+    /// The AVX2 scans, compiled with AVX2 enabled
+    ///
+    /// The scans are generic over the condition so cannot be held as function pointers, instead
+    /// each call checks the cached detection, see [`use_avx2`].
+    ///
+    /// # Safety
+    ///
+    /// Each function carries the safety requirements of the scan it wraps, and must only be called
+    /// once [`use_avx2`] has returned `true`.
+    pub(crate) mod avx2 {
+        use crate::arch::wide::scan;
+        use crate::Condition;
+
+        #[target_feature(enable = "avx2")]
+        pub unsafe fn search<F: Condition>(data: &[u8], cond: F) -> Option<usize> {
+            scan::search(data, cond)
+        }
+
+        #[target_feature(enable = "avx2")]
+        pub unsafe fn rsearch<F: Condition>(data: &[u8], cond: F) -> Option<usize> {
+            scan::rsearch(data, cond)
+        }
+
+        #[target_feature(enable = "avx2")]
+        pub unsafe fn count<F: Condition>(data: &[u8], cond: F) -> usize {
+            scan::count(data, cond)
+        }
+
+        #[target_feature(enable = "avx2")]
+        pub unsafe fn first_invalid<F: Condition>(data: &[u8], cond: F) -> Option<usize> {
+            scan::first_invalid(data, cond)
+        }
+
+        #[target_feature(enable = "avx2")]
+        pub unsafe fn for_all_ensure<F: Condition>(data: &[u8], cond: F) -> bool {
+            scan::for_all_ensure(data, cond)
+        }
+
+        #[target_feature(enable = "avx2")]
+        pub unsafe fn for_all_ensure_ct<F: Condition>(data: &[u8], cond: F, res: &mut bool) {
+            scan::for_all_ensure_ct(data, cond, res)
+        }
+
+        #[cfg(feature = "require")]
+        #[target_feature(enable = "avx2")]
+        pub unsafe fn ensure_requirements<R: crate::require::Requirement>(data: &[u8], req: R) -> R {
+            scan::ensure_requirements(data, req)
+        }
+    }
+
+    /// Whether `len` bytes are scanned by the AVX2 backend, with a condition looking `lookbehind`
+    /// bytes behind each byte
+    ///
+    /// The context for conditions which look behind is only carried between SSE2 vectors.
+    #[inline(always)]
+    pub(crate) fn use_avx2(len: usize, lookbehind: usize) -> bool {
+        len >= crate::arch::avx2::WIDTH && lookbehind == 0 && has_avx2()
+    }
+
+    /// Run the scan `$scan` on the backend detected at runtime
+    macro_rules! dispatch {
+        (@$lookbehind:expr, $scan:ident($data:ident $(, $arg:expr)*)) => {
+            if $crate::dispatch::use_avx2($data.len(), $lookbehind) {
+                $crate::dispatch::avx2::$scan($data $(, $arg)*)
+            } else {
+                $crate::arch::scan::$scan($data $(, $arg)*)
+            }
+        };
+        // requirements never look behind
+        (ensure_requirements($data:ident, $req:expr $(,)?)) => {
+            dispatch!(@0, ensure_requirements($data, $req))
+        };
+        ($scan:ident($data:ident, $cond:ident $(, $arg:expr)* $(,)?)) => {
+            dispatch!(@$crate::Condition::lookbehind(&$cond), $scan($data, $cond $(, $arg)*))
+        };
+    }
+);
+
+#[cfg(all(test, not(mirai)))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backend_is_stable() {
+        assert_eq!(backend(), backend());
+        assert_eq!(backend().to_string(), backend().name());
+    }
+
+    cfg_dispatch!(
+        use quickcheck::quickcheck;
+        use crate::{any, at_least, at_most, byte_set, eq, exactly, not, range, arch, ByteSet, Condition, Expr};
+        extern crate alloc;
+        use alloc::vec::Vec;
+
+        /// Whether each AVX2 scan agrees with the SSE2 scan on the `data`
+        fn agrees<C: Condition>(data: &[u8], cond: C) -> bool {
+            let (mut valid, mut valid_avx2) = (true, true);
+            unsafe {
+                avx2::search(data, cond.by_ref()) == arch::scan::search(data, cond.by_ref())
+                    && avx2::rsearch(data, cond.by_ref()) == arch::scan::rsearch(data, cond.by_ref())
+                    && avx2::count(data, cond.by_ref()) == arch::scan::count(data, cond.by_ref())
+                    && avx2::first_invalid(data, cond.by_ref())
+                        == arch::scan::first_invalid(data, cond.by_ref())
+                    && avx2::for_all_ensure(data, cond.by_ref())
+                        == arch::scan::for_all_ensure(data, cond.by_ref())
+                    && {
+                        avx2::for_all_ensure_ct(data, cond.by_ref(), &mut valid_avx2);
+                        arch::scan::for_all_ensure_ct(data, cond.by_ref(), &mut valid);
+                        valid == valid_avx2
+                    }
+            }
+        }
+
+        #[test]
+        fn backend_is_avx2_when_detected() {
+            assert_eq!(has_avx2(), std::is_x86_feature_detected!("avx2"));
+            assert_eq!(backend() == Backend::Avx2, has_avx2());
+            assert_eq!(backend().width(), if has_avx2() { 32 } else { 16 });
+        }
+
+        #[test]
+        fn lookbehind_stays_on_sse2() {
+            assert!(!use_avx2(64, crate::prev::<1, _>(eq(b'a')).lookbehind()));
+            assert!(!use_avx2(arch::avx2::WIDTH - 1, 0));
+            assert_eq!(use_avx2(64, 0), has_avx2());
+        }
+
+        quickcheck! {
+            fn avx2_scans_match_baseline(s: Vec<u8>, pad: u8) -> bool {
+                if !has_avx2() { return true; }
+                // ensure the AVX2 scans have a full vector, at varying offsets
+                let mut data = alloc::vec![b'a'; arch::avx2::WIDTH + pad as usize % 40];
+                data.extend_from_slice(&s);
+                let data = data.as_slice();
+
+                let class = ByteSet::from_bytes(b"aeiou").compile();
+                agrees(data, any!(eq(b'a'), range!(b'0'..=b'9')))
+                    && agrees(data, not(range!(b'a'..=b'z')))
+                    && agrees(data, range!(>= 0x80))
+                    && agrees(data, |v| unsafe { arch::eq(v, arch::splat(b'a')) })
+                    && agrees(data, byte_set!(b'a'..=b'f' | b'0'..=b'9' | b'x' | b'_'))
+                    && agrees(data, class.by_ref())
+                    && agrees(data, ByteSet::from_bytes(b"\0\t\n\r ").compile())
+                    && agrees(data, ByteSet::from_bytes(b"0123456789abcdefgh-_XYZ").compile())
+                    && agrees(data, Expr::Or(Vec::from([Expr::Range(b'0', b'4'), Expr::Range(b'6', b'9')])).compile())
+                    && agrees(data, Expr::not(Expr::Range(b'a', b'z')).compile())
+                    && agrees(data, crate::condition::AnyOf(&(eq(b'a'), range!(b'0'..=b'9'), class)))
+                    && agrees(data, exactly!(1; eq(b'a'), range!(b'a'..=b'f'), not(range!(b'b'..=b'z'))))
+                    && agrees(data, at_least!(2; range!(b'a'..=b'm'), range!(b'g'..=b'z'), eq(b'x')))
+                    && agrees(data, at_most!(1; range!(b'0'..=b'9'), range!(>= 0x80), range!(b'5'..=0xA0)))
+            }
+        }
+    );
+}
@@ -0,0 +1,595 @@
+//! Conditions as expression trees
+//!
+//! The conditions built from [`eq`], [`range!`], [`any!`] and friends are evaluated exactly as
+//! written, so `any!(range!(b'a'..=b'm'), range!(b'n'..=b'z'))` costs two range checks and
+//! `not(not(x))` two negations. An [`Expr`] describes the same conditions as data, which
+//! [`Expr::simplify`] can rewrite into the cheapest equivalent before it is evaluated.
+//!
+//! [`eq`]: crate::eq
+//! [`range!`]: crate::range
+//! [`any!`]: crate::any
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use crate::arch::{self, Vector};
+use crate::byte_set::Strategy;
+use crate::{condition, ByteSet, Condition, Nibbles};
+use core::fmt;
+
+/// A condition as an expression tree
+///
+/// Building the tree by hand or from configuration and then calling [`simplify`] gives the
+/// cheapest equivalent condition the simplifier knows of, [`compile`] lowers that to a
+/// [`Condition`].
+///
+/// # Example
+///
+/// ```
+/// use swift_check::{Expr, for_all_ensure};
+///
+/// let lower = Expr::Or(vec![Expr::Range(b'a', b'm'), Expr::Range(b'n', b'z')]);
+/// let simple = lower.simplify();
+///
+/// assert_eq!(simple, Expr::Range(b'a', b'z'));
+/// assert!(simple.cost() < lower.cost());
+/// assert!(for_all_ensure(b"hello", simple.compile()));
+///
+/// let twice = Expr::not(Expr::not(Expr::Eq(b' ')));
+/// assert_eq!(twice.simplify(), Expr::Eq(b' '));
+/// ```
+///
+/// [`simplify`]: Expr::simplify
+/// [`compile`]: Expr::compile
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    /// No byte meets the condition
+    Never,
+    /// Every byte meets the condition
+    Always,
+    /// The byte equals the value
+    Eq(u8),
+    /// The byte is within `min..=max`, empty if `min` exceeds `max`
+    Range(u8, u8),
+    /// The byte is in the set described by the lookup tables
+    Nibbles(Nibbles),
+    /// The inner condition does not hold
+    Not(Box<Expr>),
+    /// Every inner condition holds, [`Always`](Expr::Always) if there are none
+    And(Vec<Expr>),
+    /// Any inner condition holds, [`Never`](Expr::Never) if there are none
+    Or(Vec<Expr>),
+    /// Exactly one of the two conditions holds
+    Xor(Box<Expr>, Box<Expr>)
+}
+
+impl Expr {
+    /// Negate the `expr`
+    #[inline] #[must_use]
+    #[allow(clippy::should_implement_trait)] // `Expr` is not an operand
+    pub fn not(expr: Self) -> Self {
+        Self::Not(Box::new(expr))
+    }
+
+    /// Exactly one of `a` and `b` holds
+    #[inline] #[must_use]
+    pub fn xor(a: Self, b: Self) -> Self {
+        Self::Xor(Box::new(a), Box::new(b))
+    }
+
+    /// Check if the `byte` meets the condition
+    #[must_use]
+    pub fn contains(&self, byte: u8) -> bool {
+        match self {
+            Self::Never => false,
+            Self::Always => true,
+            Self::Eq(expected) => byte == *expected,
+            Self::Range(min, max) => (*min..=*max).contains(&byte),
+            Self::Nibbles(nibbles) => nibbles.contains(byte),
+            Self::Not(expr) => !expr.contains(byte),
+            Self::And(exprs) => exprs.iter().all(|expr| expr.contains(byte)),
+            Self::Or(exprs) => exprs.iter().any(|expr| expr.contains(byte)),
+            Self::Xor(a, b) => a.contains(byte) ^ b.contains(byte)
+        }
+    }
+
+    /// The bytes which meet the condition
+    #[must_use]
+    pub fn to_byte_set(&self) -> ByteSet {
+        ByteSet::from(self.table())
+    }
+
+    fn table(&self) -> [bool; 256] {
+        let mut table = [false; 256];
+        for (byte, member) in (0..=255).zip(table.iter_mut()) { *member = self.contains(byte); }
+        table
+    }
+
+    /// An estimate of the vector instructions needed to evaluate the condition, excluding loading
+    /// constants
+    ///
+    /// The cost of a [`Nibbles`](Expr::Nibbles) lookup depends on the backend, where it lacks a
+    /// native shuffle (SSE2 without SSSE3, and the fallback) each shuffle is emulated with dozens
+    /// of instructions.
+    #[must_use]
+    pub fn cost(&self) -> usize {
+        match self {
+            Self::Never | Self::Always => 0,
+            Self::Eq(_) => 1,
+            // sub, saturating sub, then compare against zero
+            Self::Range(min, max) => if min == max { 1 } else { 3 },
+            // two shuffles of the split nibbles, combined and compared, twice over when wide
+            Self::Nibbles(nibbles) => if nibbles.is_wide() { 4 * SHUFFLE + 8 } else { 2 * SHUFFLE + 6 },
+            Self::Not(expr) => 1 + expr.cost(),
+            Self::And(exprs) | Self::Or(exprs) => {
+                exprs.iter().map(Self::cost).sum::<usize>() + exprs.len().saturating_sub(1)
+            },
+            Self::Xor(a, b) => 1 + a.cost() + b.cost()
+        }
+    }
+
+    /// Rewrite the condition into a canonical, minimal, form
+    ///
+    /// The result usually only depends on which bytes meet the condition, so equivalent
+    /// expressions simplify to the same expression. It is the cheapest, by [`cost`], of:
+    ///
+    /// - Each run of consecutive bytes, as an [`Eq`] or [`Range`], combined with [`Or`]. This is
+    ///   where adjacent and overlapping ranges are merged.
+    /// - The same for the bytes which do not meet the condition, negated.
+    /// - The range from the lowest to the highest byte, excluding the runs within it which do not
+    ///   meet the condition.
+    /// - A [`Nibbles`] lookup.
+    ///
+    /// Forms comparing for more runs of bytes than a [`CompiledExpr`] checks one by one are
+    /// skipped, the lookup is always a candidate. The expression as written, after cancelling
+    /// double negations and flattening nested [`And`] and [`Or`], is returned instead when it is
+    /// cheaper still.
+    ///
+    /// [`cost`]: Expr::cost
+    /// [`Eq`]: Expr::Eq
+    /// [`Range`]: Expr::Range
+    /// [`Or`]: Expr::Or
+    /// [`And`]: Expr::And
+    /// [`Nibbles`]: Expr::Nibbles
+    #[must_use]
+    pub fn simplify(&self) -> Self {
+        let canonical = Self::canonical(&self.table());
+        let written = self.reduce();
+        if written.cost() < canonical.cost() { written } else { canonical }
+    }
+
+    /// The cheapest of the canonical forms for the bytes set in the `table`, see
+    /// [`simplify`](Expr::simplify)
+    fn canonical(table: &[bool; 256]) -> Self {
+        let (first, last) = match (
+            table.iter().position(|member| *member),
+            table.iter().rposition(|member| *member)
+        ) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return Self::Never
+        };
+        if table.iter().all(|member| *member) { return Self::Always; }
+
+        let hull = Self::Range(first as u8, last as u8).reduce();
+        let holes = Self::runs(&table[first..=last], first, false);
+
+        // on a tie prefer the earlier, more readable, form
+        [
+            Self::runs(table, 0, true),
+            Self::negate(Self::runs(table, 0, false)),
+            if holes == Self::Never { hull } else { Self::And(Vec::from([hull, Self::negate(holes)])) },
+            Self::Nibbles(Nibbles::new(table))
+        ]
+            .into_iter()
+            .filter(|expr| Lowered::of(expr).is_some())
+            .min_by_key(Self::cost)
+            .unwrap_or(Self::Never)
+    }
+
+    /// The runs of bytes whose membership in the `table` is `member`, where the table starts at
+    /// the byte `base`
+    fn runs(table: &[bool], base: usize, member: bool) -> Self {
+        let mut runs = Vec::new();
+        let mut idx = 0;
+        while idx < table.len() {
+            if table[idx] != member { idx += 1; continue; }
+            let start = idx;
+            while idx < table.len() && table[idx] == member { idx += 1; }
+            runs.push(Self::Range((base + start) as u8, (base + idx - 1) as u8).reduce());
+        }
+
+        match runs.len() {
+            0 => Self::Never,
+            1 => runs.remove(0),
+            _ => Self::Or(runs)
+        }
+    }
+
+    /// Negate the `expr`, cancelling a negation rather than adding a second
+    fn negate(expr: Self) -> Self {
+        match expr {
+            Self::Not(inner) => *inner,
+            Self::Never => Self::Always,
+            Self::Always => Self::Never,
+            other => Self::not(other)
+        }
+    }
+
+    /// Rewrite the expression as written without changing its shape: cancel double negations,
+    /// fold constants, and flatten nested [`And`](Expr::And) and [`Or`](Expr::Or)
+    fn reduce(&self) -> Self {
+        match self {
+            Self::Range(min, max) if min > max => Self::Never,
+            Self::Range(min, max) if min == max => Self::Eq(*min),
+            Self::Range(0, 255) => Self::Always,
+            Self::Not(expr) => Self::negate(expr.reduce()),
+            Self::And(exprs) => {
+                let mut flat = Vec::with_capacity(exprs.len());
+                for expr in exprs {
+                    match expr.reduce() {
+                        Self::Always => {},
+                        Self::Never => return Self::Never,
+                        Self::And(inner) => flat.extend(inner),
+                        other => flat.push(other)
+                    }
+                }
+                match flat.len() {
+                    0 => Self::Always,
+                    1 => flat.remove(0),
+                    _ => Self::And(flat)
+                }
+            },
+            Self::Or(exprs) => {
+                let mut flat = Vec::with_capacity(exprs.len());
+                for expr in exprs {
+                    match expr.reduce() {
+                        Self::Never => {},
+                        Self::Always => return Self::Always,
+                        Self::Or(inner) => flat.extend(inner),
+                        other => flat.push(other)
+                    }
+                }
+                match flat.len() {
+                    0 => Self::Never,
+                    1 => flat.remove(0),
+                    _ => Self::Or(flat)
+                }
+            },
+            Self::Xor(a, b) => match (a.reduce(), b.reduce()) {
+                (Self::Never, other) | (other, Self::Never) => other,
+                (Self::Always, other) | (other, Self::Always) => Self::negate(other),
+                (a, b) => Self::xor(a, b)
+            },
+            other => other.clone()
+        }
+    }
+
+    /// Describe the expression, see [`Condition::describe`]
+    fn describe(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Never => f.write_str("nothing"),
+            Self::Always => f.write_str("any byte"),
+            Self::Eq(expected) => condition::describe_byte(*expected, f),
+            Self::Range(min, max) => condition::describe_range(
+                *min, (*max as u16 + 1).saturating_sub(*min as u16), f
+            ),
+            Self::Nibbles(nibbles) => nibbles.describe(f),
+            Self::Not(expr) => {
+                f.write_str("not ")?;
+                expr.describe_operand("not", f)
+            },
+            Self::And(exprs) | Self::Or(exprs) => match (exprs.split_first(), self.connective()) {
+                (Some((first, rest)), Some(connective)) => {
+                    first.describe_operand(connective, f)?;
+                    for expr in rest {
+                        write!(f, " {connective} ")?;
+                        expr.describe_operand(connective, f)?;
+                    }
+                    Ok(())
+                },
+                (Some((only, _)), None) => only.describe(f),
+                (None, _) => f.write_str(if matches!(self, Self::And(_)) { "any byte" } else { "nothing" })
+            },
+            Self::Xor(a, b) => {
+                a.describe_operand("xor", f)?;
+                f.write_str(" xor ")?;
+                b.describe_operand("xor", f)
+            }
+        }
+    }
+
+    /// The word joining the parts of the description, see [`Condition::connective`]
+    fn connective(&self) -> Option<&'static str> {
+        match self {
+            Self::And(exprs) if exprs.len() > 1 => Some("and"),
+            Self::Or(exprs) if exprs.len() > 1 => Some("or"),
+            Self::Xor(..) => Some("xor"),
+            _ => None
+        }
+    }
+
+    /// Describe the expression as an operand joined by `connective`, see
+    /// [`condition::describe_operand`]
+    fn describe_operand(&self, connective: &str, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.connective() {
+            Some(inner) if inner != connective => {
+                f.write_str("(")?;
+                self.describe(f)?;
+                f.write_str(")")
+            },
+            _ => self.describe(f)
+        }
+    }
+
+    /// Simplify the condition and lower it to the vector instructions evaluating it, see
+    /// [`CompiledExpr`]
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swift_check::{Condition, Expr, search, not};
+    ///
+    /// let ident = Expr::Or(vec![Expr::Range(b'a', b'z'), Expr::Range(b'0', b'9'), Expr::Eq(b'_')]);
+    /// let cond = ident.compile();
+    ///
+    /// assert_eq!(search(b"snake_case2", not(cond.by_ref())), None);
+    /// assert_eq!(search(b"kebab-case", not(cond.by_ref())), Some(5));
+    /// ```
+    #[must_use]
+    pub fn compile(&self) -> CompiledExpr {
+        let table = self.table();
+        let simple = self.simplify();
+        // the expression as written is only kept when it is cheaper than every canonical form,
+        // which are always lowered
+        let lowered = Lowered::of(&simple)
+            .or_else(|| Lowered::of(&Self::canonical(&table)))
+            .unwrap_or(Lowered { strategy: Strategy::Nibbles(Nibbles::new(&table)), hull: None, invert: false });
+
+        CompiledExpr { expr: simple, set: ByteSet::from(table), lowered }
+    }
+}
+
+impl fmt::Display for Expr {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.describe(f)
+    }
+}
+
+/// The instructions a shuffle costs, without a native shuffle each of the 15 selects narrowing
+/// down the table takes three, and testing each of the four bits of the index another two
+const SHUFFLE: usize = if arch::NATIVE_SHUFFLE { 1 } else { 53 };
+
+/// A simplified expression lowered to the membership checks of a [`ByteClass`]
+///
+/// Each canonical form of [`Expr::simplify`] is a class of bytes, optionally within a hull and
+/// negated, so evaluating it is a fixed sequence of instructions rather than a walk of the tree.
+///
+/// [`ByteClass`]: crate::ByteClass
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Lowered {
+    strategy: Strategy,
+    /// The single byte or range holding the bytes, the `strategy` then checks for the holes
+    /// within it
+    hull: Option<Strategy>,
+    /// If the result is negated
+    invert: bool
+}
+
+impl Lowered {
+    /// Lower the `expr`, `None` if it is not one of the canonical forms or has too many runs
+    fn of(expr: &Expr) -> Option<Self> {
+        match expr {
+            Expr::Not(expr) => Self::of(expr)
+                .filter(|lowered| lowered.hull.is_none() && !lowered.invert)
+                .map(|lowered| Self { invert: true, ..lowered }),
+            Expr::Always => Some(Self { strategy: Strategy::Never, hull: None, invert: true }),
+            Expr::And(exprs) => match exprs.as_slice() {
+                [hull, Expr::Not(holes)] => {
+                    let holes = Self::of(holes).filter(|holes| holes.hull.is_none() && !holes.invert)?;
+                    match hull {
+                        Expr::Always => Some(Self { invert: true, ..holes }),
+                        Expr::Eq(_) | Expr::Range(..) => Some(Self { hull: Self::strategy(hull), ..holes }),
+                        _ => None
+                    }
+                },
+                _ => None
+            },
+            _ => Some(Self { strategy: Self::strategy(expr)?, hull: None, invert: false })
+        }
+    }
+
+    /// The membership check of the bytes meeting the `expr`, which must be a single byte, range,
+    /// lookup, or any of those
+    fn strategy(expr: &Expr) -> Option<Strategy> {
+        match expr {
+            Expr::Never => Some(Strategy::Never),
+            Expr::Eq(byte) => Some(Strategy::Eq(*byte)),
+            Expr::Range(min, max) if min > max => Some(Strategy::Never),
+            Expr::Range(min, max) => Some(Strategy::Range { min: *min, span: max - min }),
+            Expr::Nibbles(nibbles) => Some(Strategy::Nibbles(*nibbles)),
+            Expr::Or(exprs) if exprs.iter().all(|expr| matches!(expr, Expr::Eq(_) | Expr::Range(..))) => {
+                expr.to_byte_set().runs()
+            },
+            _ => None
+        }
+    }
+}
+
+/// Evaluate the `$lowered` expression for each byte of the `$data`, with the operations of the
+/// backend `$arch` and the strategies evaluated by `$eval`
+macro_rules! lowered {
+    ($arch:ident, $eval:ident, $lowered:expr, $data:expr) => {{
+        let (lowered, data) = ($lowered, $data);
+        let mut res = lowered.strategy.$eval(data);
+        if let Some(hull) = &lowered.hull {
+            res = $arch::and(hull.$eval(data), $arch::not(res));
+        }
+        if lowered.invert { res = $arch::not(res); }
+        res
+    }};
+}
+
+/// An [`Expr`] lowered to the vector instructions evaluating it, see [`Expr::compile`]
+///
+/// The expression is simplified once, then lowered in the same way as a [`ByteClass`], so
+/// evaluating a vector neither walks the tree nor allocates. Single bytes are checked against the
+/// set of bytes which meet the condition.
+///
+/// [`ByteClass`]: crate::ByteClass
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompiledExpr {
+    /// The simplified expression, kept for its description
+    expr: Expr,
+    set: ByteSet,
+    lowered: Lowered
+}
+
+impl CompiledExpr {
+    /// The simplified expression this was lowered from
+    #[inline] #[must_use]
+    pub const fn expr(&self) -> &Expr {
+        &self.expr
+    }
+}
+
+impl Condition for CompiledExpr {
+    #[inline(always)]
+    fn eval_vector(&self, data: Vector) -> Vector {
+        unsafe { lowered!(arch, eval, &self.lowered, data) }
+    }
+
+    #[inline]
+    fn eval_byte(&self, byte: u8) -> bool {
+        self.set.contains(byte)
+    }
+
+    #[inline(always)]
+    fn scalar_fast(&self) -> bool { true }
+
+    #[inline]
+    fn describe(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.expr.describe(f)
+    }
+
+    #[inline]
+    fn connective(&self) -> Option<&'static str> {
+        self.expr.connective()
+    }
+
+    eval_avx2!(|self, data| lowered!(avx2, eval_avx2, &self.lowered, data));
+}
+
+impl condition::NoLookbehind for CompiledExpr {}
+
+#[cfg(all(test, not(mirai)))]
+mod tests {
+    use super::*;
+    use crate::truth_table;
+    use quickcheck::quickcheck;
+
+    /// Build an expression from the `ops`, so quickcheck can generate and shrink it
+    fn build(ops: &mut impl Iterator<Item = u8>, depth: u8) -> Expr {
+        let (op, arg) = match (ops.next(), ops.next()) {
+            (Some(op), Some(arg)) => (op, arg),
+            _ => return Expr::Eq(0)
+        };
+        if depth == 0 { return Expr::Range(arg, arg.wrapping_add(op % 32)); }
+        match op % 8 {
+            0 => Expr::Eq(arg),
+            1 => Expr::Range(arg, arg.wrapping_add(op / 8)),
+            2 => Expr::not(build(ops, depth - 1)),
+            3 => Expr::And((0..arg % 4).map(|_| build(ops, depth - 1)).collect()),
+            4 => Expr::Or((0..arg % 4).map(|_| build(ops, depth - 1)).collect()),
+            5 => Expr::xor(build(ops, depth - 1), build(ops, depth - 1)),
+            6 => Expr::Nibbles(Nibbles::new(&build(ops, depth - 1).table())),
+            _ => if arg % 2 == 0 { Expr::Never } else { Expr::Always }
+        }
+    }
+
+    /// Lower the `expr` as written, without simplifying it first, `None` if it is not one of the
+    /// canonical forms
+    fn lowered(expr: &Expr) -> Option<CompiledExpr> {
+        Some(CompiledExpr { expr: expr.clone(), set: expr.to_byte_set(), lowered: Lowered::of(expr)? })
+    }
+
+    quickcheck! {
+        fn simplify_preserves_meaning(ops: Vec<u8>) -> bool {
+            let expr = build(&mut ops.into_iter(), 4);
+            let simple = expr.simplify();
+            truth_table(expr.compile()) == expr.table()
+                && truth_table(simple.compile()) == expr.table()
+                && lowered(&Expr::canonical(&expr.table())).is_some()
+                && lowered(&expr).map_or(true, |written| truth_table(written) == expr.table())
+                && simple.cost() <= expr.cost()
+                && simple.simplify() == simple
+        }
+    }
+
+    #[test]
+    fn merges_adjacent_ranges() {
+        let expr = Expr::Or(Vec::from([Expr::Range(b'a', b'm'), Expr::Eq(b'n'), Expr::Range(b'o', b'z')]));
+        assert_eq!(expr.simplify(), Expr::Range(b'a', b'z'));
+    }
+
+    #[test]
+    fn describes_tree() {
+        let expr = Expr::And(Vec::from([
+            Expr::Or(Vec::from([Expr::Range(b'a', b'z'), Expr::Eq(b'_')])),
+            Expr::not(Expr::Eq(b'q')),
+            Expr::Or(Vec::new())
+        ]));
+        assert_eq!(expr.to_string(), "('a'..='z' or '_') and not 'q' and nothing");
+        assert_eq!(Expr::Range(b'z', b'a').to_string(), "nothing");
+        // the compiled form describes the simplified expression
+        let compiled = Expr::Or(Vec::from([Expr::Range(b'a', b'm'), Expr::Range(b'n', b'z')])).compile();
+        assert_eq!(compiled.description().to_string(), "'a'..='z'");
+    }
+
+    #[test]
+    fn cancels_double_negation() {
+        let expr = Expr::not(Expr::not(Expr::Range(b'0', b'9')));
+        assert_eq!(expr.simplify(), Expr::Range(b'0', b'9'));
+    }
+
+    #[test]
+    fn excludes_holes_from_hull() {
+        let digits = Expr::Or(Vec::from([Expr::Range(b'0', b'4'), Expr::Range(b'6', b'9')]));
+        assert_eq!(
+            digits.simplify(),
+            Expr::And(Vec::from([Expr::Range(b'0', b'9'), Expr::not(Expr::Eq(b'5'))]))
+        );
+
+        // two runs are cheaper than the hull without its gap
+        let gap = Expr::And(Vec::from([Expr::Range(b'0', b'z'), Expr::not(Expr::Range(b':', b'@'))]));
+        assert_eq!(gap.simplify(), Expr::Or(Vec::from([Expr::Range(b'0', b'9'), Expr::Range(b'A', b'z')])));
+    }
+
+    #[test]
+    fn negates_small_complement() {
+        let expr = Expr::Or(Vec::from([Expr::Range(0, b'"' - 1), Expr::Range(b'"' + 1, 255)]));
+        assert_eq!(expr.simplify(), Expr::not(Expr::Eq(b'"')));
+    }
+
+    #[test]
+    fn switches_to_nibbles() {
+        let base64 = Expr::Or(Vec::from([
+            Expr::Range(b'A', b'Z'), Expr::Range(b'a', b'z'), Expr::Range(b'0', b'9'),
+            Expr::Eq(b'+'), Expr::Eq(b'/'), Expr::Eq(b'=')
+        ]));
+        let simple = base64.simplify();
+        assert_eq!(simple.to_byte_set(), base64.to_byte_set());
+        if arch::NATIVE_SHUFFLE {
+            assert!(matches!(simple, Expr::Nibbles(_)));
+        } else {
+            // the emulated lookup costs far more than comparing for each run
+            assert_eq!(simple, Expr::Or(Vec::from([
+                Expr::Eq(b'+'), Expr::Range(b'/', b'9'), Expr::Eq(b'='),
+                Expr::Range(b'A', b'Z'), Expr::Range(b'a', b'z')
+            ])));
+        }
+
+        // more runs than are compared for one by one
+        let alternate = Expr::Or((b'a'..=b'z').step_by(2).map(Expr::Eq).collect());
+        assert!(matches!(alternate.compile().lowered.strategy, Strategy::Nibbles(_)));
+    }
+}
@@ -0,0 +1,359 @@
+//! Integration with `std::io`
+//!
+//! [`BufReadExt`] extends any [`BufRead`] with reads driven by conditions, while
+//! [`ValidatingReader`] and [`ValidatingWriter`] ensure every byte passing through them meets a
+//! condition.
+//!
+//! Validation failures are reported as an [`io::Error`] of kind [`InvalidData`], its inner error
+//! is the [`Invalid`] byte with its offset relative to the start of the stream.
+//!
+//! ```
+//! use swift_check::{io::ValidatingReader, Invalid, range};
+//! use std::io::Read;
+//!
+//! let mut reader = ValidatingReader::new(b"hello\x00world".as_slice(), range!(0x20..=0x7E));
+//! let mut out = Vec::new();
+//!
+//! let err = reader.read_to_end(&mut out).unwrap_err();
+//! let invalid = err.get_ref().and_then(|e| e.downcast_ref::<Invalid>()).unwrap();
+//!
+//! assert_eq!(invalid.offset(), 5);
+//! ```
+//!
+//! [`InvalidData`]: io::ErrorKind::InvalidData
+
+use crate::{search, span, Condition, Invalid, StreamScanner};
+use std::io::{self, BufRead, Read, Write};
+
+#[inline]
+fn invalid_data(invalid: Invalid) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, invalid)
+}
+
+/// Extends [`BufRead`] with reads driven by conditions
+pub trait BufReadExt: BufRead {
+    /// Read all bytes into `buf` until a byte meeting the `cond` or EOF is reached
+    ///
+    /// This works like [`BufRead::read_until`], the byte meeting the `cond` is included in `buf`.
+    ///
+    /// # Returns
+    ///
+    /// The number of bytes read, `0` if EOF had already been reached.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swift_check::{io::BufReadExt, any, eq};
+    ///
+    /// let mut input = b"key=value;next".as_slice();
+    /// let mut buf = Vec::new();
+    ///
+    /// input.read_until_cond(any!(eq(b'='), eq(b';')), &mut buf).unwrap();
+    /// assert_eq!(buf, b"key=");
+    /// ```
+    fn read_until_cond<F: Condition>(
+        &mut self, cond: F, buf: &mut Vec<u8>
+    ) -> io::Result<usize> {
+        let mut read = 0;
+        loop {
+            let (done, used) = {
+                let available = match self.fill_buf() {
+                    Ok(available) => available,
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(e) => return Err(e)
+                };
+                match search(available, cond.by_ref()) {
+                    Some(pos) => {
+                        buf.extend_from_slice(&available[..=pos]);
+                        (true, pos + 1)
+                    },
+                    None => {
+                        buf.extend_from_slice(available);
+                        (available.is_empty(), available.len())
+                    }
+                }
+            };
+            self.consume(used);
+            read += used;
+            if done { return Ok(read); }
+        }
+    }
+
+    /// Skip each byte meeting the `cond` until one which does not or EOF is reached
+    ///
+    /// The first byte which did not meet the `cond` is left in the reader.
+    ///
+    /// # Returns
+    ///
+    /// The number of bytes skipped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swift_check::{io::BufReadExt, any, eq};
+    /// use std::io::BufRead;
+    ///
+    /// let mut input = b" \t\r\nbody".as_slice();
+    ///
+    /// assert_eq!(input.skip_while_cond(any!(eq(b' '), eq(b'\t'), eq(b'\r'), eq(b'\n'))).unwrap(), 4);
+    /// assert_eq!(input.fill_buf().unwrap(), b"body");
+    /// ```
+    fn skip_while_cond<F: Condition>(&mut self, cond: F) -> io::Result<usize> {
+        let mut skipped = 0;
+        loop {
+            let (done, used) = {
+                let available = match self.fill_buf() {
+                    Ok(available) => available,
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(e) => return Err(e)
+                };
+                let len = span(available, cond.by_ref());
+                (len < available.len() || available.is_empty(), len)
+            };
+            self.consume(used);
+            skipped += used;
+            if done { return Ok(skipped); }
+        }
+    }
+}
+
+impl<R: BufRead + ?Sized> BufReadExt for R {}
+
+/// A reader ensuring each byte read meets a condition
+///
+/// The bytes preceding one which fails to meet the condition are returned by the read which
+/// encountered it, the bytes following it are discarded. The next read, and all following reads,
+/// return an [`io::Error`] of kind [`InvalidData`] wrapping the [`Invalid`] byte. If the invalid
+/// byte is the first read the error is returned immediately.
+///
+/// # Example
+///
+/// ```
+/// use swift_check::{io::ValidatingReader, range};
+/// use std::io::Read;
+///
+/// let mut reader = ValidatingReader::new(b"hello\x00world".as_slice(), range!(0x20..=0x7E));
+/// let mut buf = [0u8; 16];
+///
+/// assert_eq!(reader.read(&mut buf).unwrap(), 5);
+/// assert_eq!(&buf[..5], b"hello");
+/// assert!(reader.read(&mut buf).is_err());
+/// ```
+///
+/// [`InvalidData`]: io::ErrorKind::InvalidData
+pub struct ValidatingReader<R, F> {
+    inner: R,
+    scanner: StreamScanner<F>
+}
+
+impl<R: Read, F: Condition> ValidatingReader<R, F> {
+    /// Create a new `ValidatingReader` ensuring each byte read from `inner` meets the `cond`
+    #[inline] #[must_use]
+    pub const fn new(inner: R, cond: F) -> Self {
+        Self { inner, scanner: StreamScanner::new(cond) }
+    }
+
+    /// The number of bytes read from the inner reader
+    #[inline] #[must_use]
+    pub const fn position(&self) -> usize {
+        self.scanner.position()
+    }
+
+    /// Get a reference to the inner reader
+    #[inline] #[must_use]
+    pub const fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the inner reader
+    ///
+    /// Reading directly from the inner reader bypasses validation and offsets reported by this
+    /// reader will no longer account for those bytes.
+    #[inline] #[must_use]
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Unwrap this `ValidatingReader`, returning the inner reader
+    #[inline] #[must_use]
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read, F: Condition> Read for ValidatingReader<R, F> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.scanner.result().map_err(invalid_data)?;
+        let read = self.inner.read(buf)?;
+        let valid = self.scanner.valid_len(&buf[..read]);
+        self.scanner.skip(&buf[..valid]);
+        if valid == read { return Ok(read); }
+
+        // record the failure for the following reads, returning the valid bytes before it first
+        let failed = self.scanner.validate(&buf[valid..read]).map_err(invalid_data);
+        match valid {
+            0 => failed.map(|()| 0),
+            _ => Ok(valid)
+        }
+    }
+}
+
+/// A writer ensuring each byte written meets a condition
+///
+/// The bytes preceding one which fails to meet the condition are written, the invalid byte and
+/// all following writes are rejected with an [`io::Error`] of kind [`InvalidData`] wrapping the
+/// [`Invalid`] byte.
+///
+/// # Example
+///
+/// ```
+/// use swift_check::{io::ValidatingWriter, range};
+/// use std::io::Write;
+///
+/// let mut writer = ValidatingWriter::new(Vec::new(), range!(0x20..=0x7E));
+///
+/// assert!(writer.write_all(b"hello ").is_ok());
+/// assert!(writer.write_all(b"world\x07").is_err());
+/// // the writer remains failed
+/// assert!(writer.write_all(b"!").is_err());
+/// assert_eq!(writer.get_ref(), b"hello world");
+/// ```
+///
+/// [`InvalidData`]: io::ErrorKind::InvalidData
+pub struct ValidatingWriter<W, F> {
+    inner: W,
+    scanner: StreamScanner<F>
+}
+
+impl<W: Write, F: Condition> ValidatingWriter<W, F> {
+    /// Create a new `ValidatingWriter` ensuring each byte written to `inner` meets the `cond`
+    #[inline] #[must_use]
+    pub const fn new(inner: W, cond: F) -> Self {
+        Self { inner, scanner: StreamScanner::new(cond) }
+    }
+
+    /// The number of bytes written to the inner writer
+    #[inline] #[must_use]
+    pub const fn position(&self) -> usize {
+        match self.scanner.result() {
+            // the invalid byte was seen by the scanner, but never written
+            Err(invalid) => invalid.offset(),
+            Ok(()) => self.scanner.position()
+        }
+    }
+
+    /// Get a reference to the inner writer
+    #[inline] #[must_use]
+    pub const fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the inner writer
+    ///
+    /// Writing directly to the inner writer bypasses validation and offsets reported by this
+    /// writer will no longer account for those bytes.
+    #[inline] #[must_use]
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Unwrap this `ValidatingWriter`, returning the inner writer
+    #[inline] #[must_use]
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write, F: Condition> Write for ValidatingWriter<W, F> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.scanner.result().map_err(invalid_data)?;
+        let valid = self.scanner.valid_len(buf);
+        if valid == 0 && !buf.is_empty() {
+            // nothing can be written, record the failure and report it
+            return self.scanner.validate(&buf[..1]).map(|()| 0).map_err(invalid_data);
+        }
+
+        let written = self.inner.write(&buf[..valid])?;
+        self.scanner.skip(&buf[..written]);
+        if written == valid && valid < buf.len() {
+            // the valid bytes were all written, the invalid byte fails the following writes
+            let _ = self.scanner.validate(&buf[valid..=valid]);
+        }
+        Ok(written)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(all(test, not(mirai)))]
+mod tests {
+    use super::*;
+    use crate::{eq, range, validate};
+    use quickcheck::quickcheck;
+    use std::io::BufReader;
+
+    fn invalid_of(err: &io::Error) -> Option<Invalid> {
+        err.get_ref().and_then(|e| e.downcast_ref::<Invalid>()).copied()
+    }
+
+    quickcheck! {
+        fn read_until_cond_matches_read_until(s: Vec<u8>, cap: u8) -> bool {
+            let mut ours = BufReader::with_capacity(cap as usize % 40 + 1, s.as_slice());
+            let mut theirs = s.as_slice();
+            loop {
+                let (mut a, mut b) = (Vec::new(), Vec::new());
+                let read = ours.read_until_cond(eq(b'\n'), &mut a).unwrap();
+                if read != theirs.read_until(b'\n', &mut b).unwrap() || a != b { return false; }
+                if read == 0 { return true; }
+            }
+        }
+        fn skip_while_cond_matches_scalar(s: Vec<u8>, cap: u8) -> bool {
+            let mut reader = BufReader::with_capacity(cap as usize % 40 + 1, s.as_slice());
+            let skipped = reader.skip_while_cond(range!(>= 8)).unwrap();
+            let mut rest = Vec::new();
+            reader.read_to_end(&mut rest).unwrap();
+            skipped == s.iter().take_while(|b| **b >= 8).count() && rest == s[skipped..]
+        }
+        fn validating_reader_reports_first_invalid(s: Vec<u8>, cap: u8) -> bool {
+            let inner = BufReader::with_capacity(cap as usize % 40 + 1, s.as_slice());
+            let mut reader = ValidatingReader::new(inner, range!(>= 8));
+            let mut out = Vec::new();
+            match (reader.read_to_end(&mut out), validate(&s, range!(>= 8))) {
+                (Ok(_), Ok(())) => out == s,
+                (Err(e), Err(invalid)) => invalid_of(&e) == Some(invalid)
+                    && out == s[..invalid.offset()],
+                _ => false
+            }
+        }
+        fn validating_writer_writes_valid_prefix(s: Vec<u8>) -> bool {
+            let mut writer = ValidatingWriter::new(Vec::new(), range!(>= 8));
+            let mut res = Ok(());
+            for chunk in s.chunks(7) {
+                res = writer.write_all(chunk);
+                if res.is_err() { break; }
+            }
+            match (res, validate(&s, range!(>= 8))) {
+                (Ok(()), Ok(())) => writer.get_ref() == &s,
+                (Err(e), Err(invalid)) => invalid_of(&e) == Some(invalid)
+                    && writer.get_ref() == &s[..invalid.offset()],
+                _ => false
+            }
+        }
+        fn validating_writer_stays_failed(s: Vec<u8>, rest: Vec<u8>) -> bool {
+            let mut writer = ValidatingWriter::new(Vec::new(), range!(>= 8));
+            // a single write, unlike `write_all`, does not retry the invalid byte
+            let first = writer.write(&s);
+            match validate(&s, range!(>= 8)) {
+                Ok(()) => first.ok() == Some(s.len())
+                    && writer.write_all(&rest).is_ok() == validate(&rest, range!(>= 8)).is_ok(),
+                Err(invalid) => first.ok() == Some(invalid.offset()).filter(|offset| *offset > 0)
+                    && writer.write(&rest).map_err(|e| invalid_of(&e)) == Err(Some(invalid))
+                    && writer.position() == invalid.offset()
+                    && writer.get_ref() == &s[..invalid.offset()]
+            }
+        }
+    }
+}
@@ -31,11 +31,47 @@
 #![cfg_attr(not(any(test, mirai, feature = "verify")), no_builtins)]
 #![cfg_attr(docsrs, feature(doc_auto_cfg))]
 
+#[macro_use]
 pub mod arch;
 
+#[macro_use]
+mod dispatch;
+pub use dispatch::{backend, Backend};
+
 #[cfg(feature = "require")]
 pub mod require;
 
+#[cfg(feature = "std")]
+pub mod io;
+
+mod split;
+pub use split::{split, splitn, split_inclusive, Split, SplitN, SplitInclusive};
+
+mod stream;
+pub use stream::{StreamScanner, StreamMatches};
+
+mod memmem;
+pub use memmem::{find_substring, Finder, FindIter};
+
+mod byte_set;
+pub use byte_set::{ByteClass, ByteSet, Nibbles};
+
+pub mod condition;
+pub use condition::{Cond, Condition, truth_table};
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "alloc")]
+pub mod expr;
+#[cfg(feature = "alloc")]
+pub use expr::{CompiledExpr, Expr};
+
+#[cfg(feature = "regex")]
+pub mod regex;
+#[cfg(feature = "regex")]
+pub use regex::from_regex_class;
+
 use arch::Vector;
 
 /// Check that the condition holds for all bytes
@@ -67,14 +103,14 @@ use arch::Vector;
 /// **Note**: This is part of the lower-level api, for better ergonomics see [`for_all_ensure`] or
 /// [`for_all_ensure_ct`] if branching on the data's contents is unacceptable for security reasons.
 #[inline(always)] #[must_use]
-pub fn ensure(data: Vector, cond: impl Fn(Vector) -> Vector) -> bool {
-    unsafe { arch::MoveMask::new(cond(data)).all_bits_set() }
+pub fn ensure(data: Vector, cond: impl Condition) -> bool {
+    unsafe { arch::MoveMask::new(cond.eval_vector(data)).all_bits_set() }
 }
 
 #[macro_export] #[doc(hidden)]
 macro_rules! ensure {
     ($data:expr, $cond:expr) => {
-        unsafe { $crate::arch::MoveMask::new($cond($data)).all_bits_set() }
+        unsafe { $crate::arch::MoveMask::new($crate::Condition::eval_vector(&$cond, $data)).all_bits_set() }
     };
 }
 
@@ -107,8 +143,8 @@ macro_rules! ensure {
 ///
 /// **Note**: This is part of the lower-level api, for better ergonomics see [`search`].
 #[inline(always)]
-pub fn find(data: Vector, cond: impl Fn(Vector) -> Vector) -> Option<u32> {
-    let len = unsafe { arch::MoveMask::new(cond(data)).trailing_zeros() };
+pub fn find(data: Vector, cond: impl Condition) -> Option<u32> {
+    let len = unsafe { arch::MoveMask::new(cond.eval_vector(data)).trailing_zeros() };
     if len >= arch::MoveMask::MAX_TRAIL { None } else { Some(len) }
 }
 
@@ -123,7 +159,7 @@ macro_rules! __is_found {
 #[doc(hidden)] #[macro_export]
 macro_rules! find {
     ($data:expr, $cond:expr) => {
-        $crate::__is_found!($cond($data), |__len| Some(__len), || None)
+        $crate::__is_found!($crate::Condition::eval_vector(&$cond, $data), |__len| Some(__len), || None)
     };
 }
 
@@ -140,7 +176,7 @@ macro_rules! find {
 ///
 /// * `data` - The data to validate
 /// * `cond` - The condition to validate with, this should be some composition of the conditions
-///            exposed within this crate.
+///   exposed within this crate.
 ///
 /// # Returns
 ///
@@ -162,15 +198,15 @@ macro_rules! find {
 /// assert!(!should_fail);
 /// ```
 #[inline] #[must_use]
-pub fn for_all_ensure_ct(data: &[u8], cond: impl Fn(Vector) -> Vector) -> bool {
+pub fn for_all_ensure_ct(data: &[u8], cond: impl Condition) -> bool {
+    condition::check_lookbehind(&cond);
     let mut valid = true;
     if data.len() >= arch::WIDTH {
-        unsafe { arch::scan::for_all_ensure_ct(data, cond, &mut valid) }
+        unsafe { dispatch!(for_all_ensure_ct(data, cond, &mut valid)) }
     } else {
-        // This is a temporary solution, in the future conditions will support both simd and byte
-        // by byte checks
+        // the scalar evaluation may branch on each byte, so this always uses the partial load
         valid &= unsafe {
-            arch::MoveMask::new(cond(arch::load_partial(data, data.len())))
+            arch::MoveMask::new(cond.eval_vector(arch::load_partial(data, data.len())))
                 .trailing_ones() >= data.len() as u32
         };
     }
@@ -184,7 +220,7 @@ pub fn for_all_ensure_ct(data: &[u8], cond: impl Fn(Vector) -> Vector) -> bool {
 ///
 /// * `data` - The data to validate
 /// * `cond` - The condition to validate with, this should be some composition of the conditions
-///            exposed within this crate.
+///   exposed within this crate.
 ///
 /// # Returns
 ///
@@ -209,17 +245,325 @@ pub fn for_all_ensure_ct(data: &[u8], cond: impl Fn(Vector) -> Vector) -> bool {
 /// assert!(!should_fail);
 /// ```
 #[inline] #[must_use]
-pub fn for_all_ensure(data: &[u8], cond: impl Fn(Vector) -> Vector) -> bool {
+pub fn for_all_ensure(data: &[u8], cond: impl Condition) -> bool {
+    condition::check_lookbehind(&cond);
     if data.len() >= arch::WIDTH {
-        unsafe { arch::scan::for_all_ensure(data, cond) }
+        unsafe { dispatch!(for_all_ensure(data, cond)) }
+    } else if cond.scalar_fast() {
+        data.iter().all(|&byte| cond.eval_byte(byte))
     } else {
         unsafe {
-            arch::MoveMask::new(cond(arch::load_partial(data, data.len())))
+            arch::MoveMask::new(cond.eval_vector(arch::load_partial(data, data.len())))
                 .trailing_ones() >= data.len() as u32
         }
     }
 }
 
+/// The first byte which did not meet the condition, see [`validate`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Invalid {
+    offset: usize,
+    value: u8
+}
+
+impl Invalid {
+    /// Create a new `Invalid` instance
+    #[inline] #[must_use]
+    pub const fn new(offset: usize, value: u8) -> Self {
+        Self { offset, value }
+    }
+
+    /// The position of the invalid byte within the validated data
+    #[inline] #[must_use]
+    pub const fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// The invalid byte
+    #[inline] #[must_use]
+    pub const fn value(&self) -> u8 {
+        self.value
+    }
+
+    /// Attach the condition which was not met, so that the error describes what was expected
+    ///
+    /// [`validate_expecting`] does this for you, this is for an `Invalid` from elsewhere.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swift_check::{validate, any, range, eq};
+    ///
+    /// let is_ident = any!(range!(b'a'..=b'z'), range!(b'0'..=b'9'), eq(b'_'));
+    ///
+    /// let err = validate(b"invalid-identifier", is_ident).unwrap_err().expecting(is_ident);
+    /// assert_eq!(
+    ///     err.to_string(),
+    ///     "invalid byte 0x2d at offset 7, expected 'a'..='z' or '0'..='9' or '_'"
+    /// );
+    /// ```
+    #[inline] #[must_use]
+    pub const fn expecting<C: Condition>(self, cond: C) -> Unexpected<C> {
+        Unexpected { invalid: self, cond }
+    }
+}
+
+impl core::fmt::Display for Invalid {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "invalid byte {:#04x} at offset {}", self.value, self.offset)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Invalid {}
+
+/// An [`Invalid`] byte along with the condition it did not meet, see [`Invalid::expecting`]
+#[derive(Copy, Clone)]
+pub struct Unexpected<C> {
+    invalid: Invalid,
+    cond: C
+}
+
+impl<C: Condition> Unexpected<C> {
+    /// The position and value of the byte which did not meet the condition
+    #[inline] #[must_use]
+    pub const fn invalid(&self) -> Invalid {
+        self.invalid
+    }
+
+    /// The condition which was not met
+    #[inline] #[must_use]
+    pub const fn cond(&self) -> &C {
+        &self.cond
+    }
+}
+
+impl<C: Condition> core::fmt::Display for Unexpected<C> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "{}, expected {}", self.invalid, self.cond.description())
+    }
+}
+
+impl<C: Condition> core::fmt::Debug for Unexpected<C> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_struct("Unexpected")
+            .field("invalid", &self.invalid)
+            .field("expected", &format_args!("{}", self.cond.description()))
+            .finish()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<C: Condition> std::error::Error for Unexpected<C> {}
+
+/// Validate that each byte meets the `cond`, reporting where it did not
+///
+/// This is [`for_all_ensure`] for when you need to know why the data was rejected.
+///
+/// # Arguments
+///
+/// * `data` - The data to validate
+/// * `cond` - The condition to validate with, this should be some composition of the conditions
+///   exposed within this crate.
+///
+/// # Returns
+///
+/// - `Ok(())` - Every byte in `data` met the `cond`.
+/// - `Err(Invalid)` - The offset and value of the first byte which did not meet the `cond`.
+///
+/// # Example
+///
+/// ```
+/// use swift_check::{validate, any, range, eq};
+///
+/// let is_ident = any!(range!(b'a'..=b'z'), range!(b'0'..=b'9'), eq(b'_'));
+///
+/// assert!(validate(b"valid_identifier_2", is_ident).is_ok());
+///
+/// let err = validate(b"invalid-identifier", is_ident).unwrap_err();
+/// assert_eq!(err.offset(), 7);
+/// assert_eq!(err.value(), b'-');
+/// assert_eq!(err.to_string(), "invalid byte 0x2d at offset 7");
+/// ```
+#[inline]
+pub fn validate(data: &[u8], cond: impl Condition) -> Result<(), Invalid> {
+    condition::check_lookbehind(&cond);
+    let invalid = if data.len() >= arch::WIDTH {
+        unsafe { dispatch!(first_invalid(data, cond)) }
+    } else if cond.scalar_fast() {
+        data.iter().position(|&byte| !cond.eval_byte(byte))
+    } else {
+        match unsafe {
+            arch::MoveMask::new(cond.eval_vector(arch::load_partial(data, data.len()))).trailing_ones()
+        } {
+            offset if offset < data.len() as u32 => Some(offset as usize),
+            _ => None
+        }
+    };
+
+    match invalid {
+        Some(offset) => Err(Invalid::new(offset, data[offset])),
+        None => Ok(())
+    }
+}
+
+/// Validate that each byte meets the `cond`, reporting where it did not and what was expected
+///
+/// This is [`validate`] with the failure carrying the `cond`, so it describes itself, see
+/// [`Invalid::expecting`].
+///
+/// # Arguments
+///
+/// * `data` - The data to validate
+/// * `cond` - The condition to validate with, its [`description`] is used in the error.
+///
+/// # Returns
+///
+/// - `Ok(())` - Every byte in `data` met the `cond`.
+/// - `Err(Unexpected)` - The offset and value of the first byte which did not meet the `cond`,
+///   along with the `cond` itself.
+///
+/// # Example
+///
+/// ```
+/// use swift_check::{validate_expecting, named, any, range, eq};
+///
+/// let is_ident = any!(named("letter", range!(b'a'..=b'z')), range!(b'0'..=b'9'), eq(b'_'));
+///
+/// assert!(validate_expecting(b"valid_identifier_2", is_ident).is_ok());
+///
+/// let err = validate_expecting(b"invalid-identifier", is_ident).unwrap_err();
+/// assert_eq!(err.invalid().offset(), 7);
+/// assert_eq!(
+///     err.to_string(),
+///     "invalid byte 0x2d at offset 7, expected letter or '0'..='9' or '_'"
+/// );
+/// ```
+///
+/// [`description`]: Condition::description
+#[inline]
+pub fn validate_expecting<C: Condition>(data: &[u8], cond: C) -> Result<(), Unexpected<C>> {
+    validate(data, cond.by_ref()).map_err(|invalid| invalid.expecting(cond))
+}
+
+/// Get the length of the leading run of bytes meeting the `cond`
+///
+/// This is akin to `strspn` in C.
+///
+/// # Arguments
+///
+/// * `data` - The data to measure the leading run of
+/// * `cond` - The condition each byte in the run must meet
+///
+/// # Returns
+///
+/// The number of leading bytes which met the `cond`, if every byte met the `cond` this is
+/// `data.len()`.
+///
+/// # Example
+///
+/// ```
+/// use swift_check::{span, range};
+///
+/// let input = b"12345 is the number";
+/// assert_eq!(span(input, range!(b'0'..=b'9')), 5);
+/// assert_eq!(span(b"12345", range!(b'0'..=b'9')), 5);
+/// assert_eq!(span(b"no numbers", range!(b'0'..=b'9')), 0);
+/// ```
+#[inline] #[must_use]
+pub fn span(data: &[u8], cond: impl Condition) -> usize {
+    condition::check_lookbehind(&cond);
+    if data.len() >= arch::WIDTH {
+        unsafe { dispatch!(first_invalid(data, cond)) }.unwrap_or(data.len())
+    } else if cond.scalar_fast() {
+        data.iter().position(|&byte| !cond.eval_byte(byte)).unwrap_or(data.len())
+    } else {
+        // the zeroes padding the partial load may meet the condition, extending the run beyond
+        // the data.
+        let len = unsafe {
+            arch::MoveMask::new(cond.eval_vector(arch::load_partial(data, data.len()))).trailing_ones()
+        };
+        (len as usize).min(data.len())
+    }
+}
+
+/// Remove the leading bytes meeting the `cond`
+///
+/// # Arguments
+///
+/// * `data` - The data to trim
+/// * `cond` - The condition the removed bytes must meet
+///
+/// # Returns
+///
+/// The subslice of `data` beginning at the first byte which did not meet the `cond`.
+///
+/// # Example
+///
+/// ```
+/// use swift_check::{trim_start, any, eq};
+///
+/// let input = b"  \t  hello world  ";
+/// assert_eq!(trim_start(input, any!(eq(b' '), eq(b'\t'))), b"hello world  ");
+/// ```
+#[inline] #[must_use]
+pub fn trim_start(data: &[u8], cond: impl Condition) -> &[u8] {
+    &data[span(data, cond)..]
+}
+
+/// Remove the trailing bytes meeting the `cond`
+///
+/// # Arguments
+///
+/// * `data` - The data to trim
+/// * `cond` - The condition the removed bytes must meet
+///
+/// # Returns
+///
+/// The subslice of `data` ending at the last byte which did not meet the `cond`.
+///
+/// # Example
+///
+/// ```
+/// use swift_check::{trim_end, any, eq};
+///
+/// let input = b"  hello world \r\n";
+/// assert_eq!(trim_end(input, any!(eq(b' '), eq(b'\r'), eq(b'\n'))), b"  hello world");
+/// ```
+#[inline] #[must_use]
+pub fn trim_end(data: &[u8], cond: impl Condition) -> &[u8] {
+    match rsearch(data, not(cond)) {
+        Some(last) => &data[..=last],
+        None => &[]
+    }
+}
+
+/// Remove the leading and trailing bytes meeting the `cond`
+///
+/// # Arguments
+///
+/// * `data` - The data to trim
+/// * `cond` - The condition the removed bytes must meet
+///
+/// # Returns
+///
+/// The subslice of `data` which neither begins nor ends with a byte meeting the `cond`.
+///
+/// # Example
+///
+/// ```
+/// use swift_check::{trim, any, eq};
+///
+/// let input = b"\t  hello world \r\n";
+/// let whitespace = any!(eq(b' '), eq(b'\t'), eq(b'\r'), eq(b'\n'));
+/// assert_eq!(trim(input, whitespace), b"hello world");
+/// assert_eq!(trim(b"   ", whitespace), b"");
+/// ```
+#[inline] #[must_use]
+pub fn trim(data: &[u8], cond: impl Condition) -> &[u8] {
+    trim_end(trim_start(data, cond.by_ref()), cond)
+}
+
 /// Find the first byte that meets the `cond`
 ///
 /// # Arguments
@@ -247,17 +591,250 @@ pub fn for_all_ensure(data: &[u8], cond: impl Fn(Vector) -> Vector) -> bool {
 /// }
 /// ```
 #[inline]
-pub fn search(data: &[u8], cond: impl Fn(Vector) -> Vector) -> Option<usize> {
+pub fn search(data: &[u8], cond: impl Condition) -> Option<usize> {
+    condition::check_lookbehind(&cond);
     if data.len() >= arch::WIDTH {
-        unsafe { arch::scan::search(data, cond) }
+        unsafe { dispatch!(search(data, cond)) }
+    } else if cond.scalar_fast() {
+        data.iter().position(|&byte| cond.eval_byte(byte))
     } else {
-        match unsafe { arch::MoveMask::new(cond(arch::load_partial(data, data.len()))).trailing_zeros() } {
+        match unsafe { arch::MoveMask::new(cond.eval_vector(arch::load_partial(data, data.len()))).trailing_zeros() } {
             offset if offset < data.len() as u32 => Some(offset as usize),
             _ => None
         }
     }
 }
 
+/// Find the first position where consecutive bytes meet a sequence of conditions
+///
+/// The sequence is searched for where its last byte would be, each condition before the last
+/// looking behind by its distance from the end, as with [`prev`]. So each vector is loaded once,
+/// and the bytes before it shifted in, finding the sequence where it straddles two vectors.
+///
+/// As the sequence looks behind, it is always scanned by the SSE2 backend on `x86_64`, see
+/// [`backend`]. Conditions in the sequence may look behind themselves, so long as none looks back
+/// more than 16 bytes from the last byte of the sequence, the limit of [`prev`].
+///
+/// # Arguments
+///
+/// * `data`  - The data to search
+/// * `conds` - A tuple of up to sixteen conditions the consecutive bytes must meet, in order
+///
+/// # Returns
+///
+/// - `Some(position)` - The position of the first byte of the first occurrence of the sequence.
+/// - `None` - The sequence does not occur within the `data`.
+///
+/// # Example
+///
+/// ```
+/// use swift_check::{search_seq, range, eq};
+///
+/// let input = b"meeting moved from 9:30 to 10:45, see you there";
+/// let digit = range!(b'0'..=b'9');
+///
+/// assert_eq!(search_seq(input, (digit, digit, eq(b':'), digit, digit)), Some(27));
+/// assert_eq!(search_seq(b"line one\nline two\r\n", (eq(b'\r'), eq(b'\n'))), Some(17));
+/// ```
+#[inline]
+pub fn search_seq<S: condition::Sequence>(data: &[u8], conds: S) -> Option<usize> {
+    // found at its last byte, the conditions before it never hold before the start of the data
+    search(data, conds.into_cond()).map(|end| end + 1 - S::LEN)
+}
+
+/// Find the first byte meeting any of the conditions, and which condition it met
+///
+/// The conditions are searched for as one, with [`search`], and only the byte found is tested
+/// against each of them, so a search for any of several tokens also tells which was found.
+///
+/// # Arguments
+///
+/// * `data`  - The data to search
+/// * `conds` - A tuple of up to twelve conditions to search for, a byte meeting several belongs to
+///   the first of them
+///
+/// # Returns
+///
+/// - `Some((position, index))` - The position of the first byte meeting any of the `conds`, and
+///   the index of the first condition in `conds` it meets.
+/// - `None` - No byte within the `data` meets any of the `conds`.
+///
+/// # Example
+///
+/// ```
+/// use swift_check::{search_classify, eq, any};
+///
+/// const QUOTE: usize = 0;
+/// const NEWLINE: usize = 2;
+/// const DELIMITER: usize = 3;
+///
+/// let classes = (eq(b'"'), eq(b'\\'), eq(b'\n'), any!(eq(b','), eq(b';')));
+/// let line = b"id,\"quoted, field\"\n";
+///
+/// let (mut tokens, mut offset) = (Vec::new(), 0);
+/// while let Some((pos, class)) = search_classify(&line[offset..], classes) {
+///     tokens.push((offset + pos, class));
+///     offset += pos + 1;
+/// }
+/// assert_eq!(tokens, [(2, DELIMITER), (3, QUOTE), (10, DELIMITER), (17, QUOTE), (18, NEWLINE)]);
+/// ```
+#[inline]
+pub fn search_classify<T: condition::Classes>(data: &[u8], conds: T) -> Option<(usize, usize)> {
+    let pos = search(data, condition::AnyOf(&conds))?;
+    // only the conditions which look behind need the vector starting at the byte found
+    conds.class(data[pos], || unsafe {
+        let rest = data.len() - pos;
+        let vector = if rest >= arch::WIDTH {
+            arch::load_at(data, pos)
+        } else {
+            arch::load_partial(&data[pos..], rest)
+        };
+        (vector, condition::Context::at(data, pos))
+    }).map(|class| (pos, class))
+}
+
+/// Find the last byte that meets the `cond`
+///
+/// # Arguments
+///
+/// * `data` - The haystack to search
+/// * `cond` - The condition to find the last occurrence of
+///
+/// # Returns
+///
+/// - `Some(position)` - The last position where the condition was met, this position will always
+///   be less than `data.len()`.
+/// - `None` - There was no circumstance of the condition being met.
+///
+/// # Example
+///
+/// ```
+/// use swift_check::{rsearch, eq, not, any};
+///
+/// let path = b"/usr/local/share/swift-check/README.md";
+/// if let Some(pos) = rsearch(path, eq(b'/')) {
+///     assert_eq!(&path[pos + 1..], b"README.md");
+/// } else {
+///     unreachable!("path contained a /");
+/// }
+///
+/// let input = b"trailing whitespace \t\r\n  ";
+/// let last = rsearch(input, not(any!(eq(b' '), eq(b'\t'), eq(b'\r'), eq(b'\n'))));
+/// assert_eq!(last, Some(18));
+/// ```
+#[inline]
+pub fn rsearch(data: &[u8], cond: impl Condition) -> Option<usize> {
+    condition::check_lookbehind(&cond);
+    if data.len() >= arch::WIDTH {
+        unsafe { dispatch!(rsearch(data, cond)) }
+    } else if cond.scalar_fast() {
+        data.iter().rposition(|&byte| cond.eval_byte(byte))
+    } else {
+        // the partial load pads with zeroes, these lanes must be ignored
+        match unsafe {
+            arch::MoveMask::new(cond.eval_vector(arch::load_partial(data, data.len())))
+                .lanes_below(data.len() as u32)
+                .leading_zeros()
+        } {
+            offset if offset < arch::WIDTH as u32 => Some(arch::WIDTH - 1 - offset as usize),
+            _ => None
+        }
+    }
+}
+
+/// Count the bytes that meet the `cond`
+///
+/// # Arguments
+///
+/// * `data` - The data to count within
+/// * `cond` - The condition each counted byte must meet
+///
+/// # Returns
+///
+/// The number of bytes in `data` which met the `cond`, this will never exceed `data.len()`.
+///
+/// # Example
+///
+/// ```
+/// use swift_check::{count, eq, range, not};
+///
+/// let log = b"GET /index.html 200\nGET /favicon.ico 404\nPOST /login 302\n";
+/// assert_eq!(count(log, eq(b'\n')), 3);
+///
+/// let payload = "caf\u{e9} na\u{ef}ve".as_bytes();
+/// assert_eq!(count(payload, not(range!(0..=127))), 4);
+/// ```
+#[inline] #[must_use]
+pub fn count(data: &[u8], cond: impl Condition) -> usize {
+    condition::check_lookbehind(&cond);
+    if data.len() >= arch::WIDTH {
+        unsafe { dispatch!(count(data, cond)) }
+    } else if cond.scalar_fast() {
+        data.iter().filter(|&&byte| cond.eval_byte(byte)).count()
+    } else {
+        // the partial load pads with zeroes, these lanes must not be counted
+        unsafe {
+            arch::MoveMask::new(cond.eval_vector(arch::load_partial(data, data.len())))
+                .lanes_below(data.len() as u32)
+                .count_ones() as usize
+        }
+    }
+}
+
+/// An iterator over each position where a condition was met
+///
+/// This is created by [`search_all`].
+pub struct Matches<'a, F>(arch::scan::Matches<'a, F>);
+
+impl<'a, F: Condition> Iterator for Matches<'a, F> {
+    type Item = usize;
+
+    #[inline]
+    fn next(&mut self) -> Option<usize> {
+        self.0.next()
+    }
+}
+
+impl<'a, F: Condition> core::iter::FusedIterator for Matches<'a, F> {}
+
+/// Find every byte that meets the `cond`
+///
+/// Unlike calling [`search`] in a loop, this makes a single pass over `data`, draining every match
+/// from each vector before loading the next.
+///
+/// # Arguments
+///
+/// * `data` - The haystack to search
+/// * `cond` - The condition to find each occurrence of
+///
+/// # Returns
+///
+/// An iterator yielding each position where the condition was met in ascending order, each
+/// position will always be less than `data.len()`.
+///
+/// # Example
+///
+/// ```
+/// use swift_check::{search_all, any, eq};
+///
+/// let input = b"name,age;location,occupation";
+/// let mut delimiters = search_all(input, any!(eq(b','), eq(b';')));
+///
+/// assert_eq!(delimiters.next(), Some(4));
+/// assert_eq!(delimiters.next(), Some(8));
+/// assert_eq!(delimiters.next(), Some(17));
+/// assert_eq!(delimiters.next(), None);
+/// ```
+#[inline]
+pub fn search_all<F: Condition>(data: &[u8], cond: F) -> Matches<'_, F> {
+    condition::check_lookbehind(&cond);
+    if data.len() >= arch::WIDTH {
+        Matches(unsafe { arch::scan::Matches::new(data, cond) })
+    } else {
+        Matches(unsafe { arch::scan::Matches::partial(data, cond) })
+    }
+}
+
 /// Ensure min is less than max at compile time
 #[doc(hidden)] #[macro_export]
 macro_rules! comp_check_rng {
@@ -330,22 +907,32 @@ macro_rules! comp_check_rng {
 #[macro_export]
 macro_rules! range {
     ($min:literal..=$max:literal) => {
-        $crate::comp_check_rng!($min, $max, $crate::arch::range::<$min, $max>())
+        $crate::comp_check_rng!($min, $max, $crate::condition::Range::new(
+            $min, $max as u16 - $min as u16 + 1, $crate::arch::range::<$min, $max>()
+        ))
     };
     ($min:literal..$max:literal) => {
-        $crate::comp_check_rng!($min, $max, $crate::arch::exclusive_range::<$min, $max>())
+        $crate::comp_check_rng!($min, $max, $crate::condition::Range::new(
+            ($min as u8).wrapping_add(1),
+            ($max as u16).saturating_sub($min as u16 + 1),
+            $crate::arch::exclusive_range::<$min, $max>()
+        ))
     };
     (<= $max:literal) => {
-        $crate::arch::less_than_or_eq::<$max>()
+        $crate::condition::Range::new(0, $max as u16 + 1, $crate::arch::less_than_or_eq::<$max>())
     };
     (< $max:literal) => {
-        $crate::arch::less_than::<$max>()
+        $crate::condition::Range::new(0, $max as u16, $crate::arch::less_than::<$max>())
     };
     (>= $min:literal) => {
-        $crate::arch::greater_than_or_eq::<$min>()
+        $crate::condition::Range::new(
+            $min, 256 - $min as u16, $crate::arch::greater_than_or_eq::<$min>()
+        )
     };
     (> $min:literal) => {
-        $crate::arch::greater_than::<$min>()
+        $crate::condition::Range::new(
+            ($min as u8).wrapping_add(1), 255 - $min as u16, $crate::arch::greater_than::<$min>()
+        )
     };
 }
 
@@ -379,8 +966,100 @@ macro_rules! range {
 /// assert!(!should_fail);
 /// ```
 #[inline(always)]
-pub const fn eq(expected: u8) -> impl Fn(Vector) -> Vector {
-    move |data| unsafe { arch::eq(data, arch::splat(expected)) }
+pub const fn eq(expected: u8) -> condition::Eq {
+    condition::Eq::new(expected)
+}
+
+/// Check that the bits of each byte selected by the `mask` equal the `value`
+///
+/// Many classes are bit patterns rather than ranges, such as UTF-8 continuation bytes being
+/// `10xxxxxx`. This costs an `and` and a comparison, without the range comparisons which are
+/// awkward on the backends only comparing signed bytes.
+///
+/// # Arguments
+///
+/// * `mask` - The bits of each byte to compare
+/// * `value` - What the selected bits must be, bits outside the `mask` never match
+///
+/// # Example
+///
+/// ```
+/// use swift_check::{masked_eq, search, not};
+///
+/// let continuation = masked_eq(0b1100_0000, 0b1000_0000);
+///
+/// let input = "héllo".as_bytes();
+/// assert_eq!(search(input, continuation), Some(2));
+/// assert_eq!(search(&input[2..], not(continuation)), Some(1));
+/// ```
+#[inline(always)]
+pub const fn masked_eq(mask: u8, value: u8) -> condition::MaskedEq {
+    condition::MaskedEq::new(mask, value)
+}
+
+/// Check that any of the bits of each byte selected by the `mask` are set
+///
+/// # Arguments
+///
+/// * `mask` - The bits of each byte to check
+///
+/// # Example
+///
+/// ```
+/// use swift_check::{any_bits, for_all_ensure, not};
+///
+/// // ascii is each byte having its high bit clear
+/// let ascii = not(any_bits(0x80));
+///
+/// assert!(for_all_ensure(b"hello world", ascii));
+/// assert!(!for_all_ensure("h\u{e9}llo".as_bytes(), ascii));
+/// ```
+#[inline(always)]
+pub const fn any_bits(mask: u8) -> condition::AnyBits {
+    condition::AnyBits::new(mask)
+}
+
+/// Check that all the bits of each byte selected by the `mask` are set
+///
+/// This is [`masked_eq`] with the `mask` as the value.
+///
+/// # Arguments
+///
+/// * `mask` - The bits of each byte which must be set
+///
+/// # Example
+///
+/// ```
+/// use swift_check::{all_bits, search};
+///
+/// const ACK: u8 = 0b0001;
+/// const FIN: u8 = 0b1000;
+///
+/// let flags = [0b0001, 0b1000, 0b1011, 0b0011];
+/// assert_eq!(search(&flags, all_bits(ACK | FIN)), Some(2));
+/// ```
+#[inline(always)]
+pub const fn all_bits(mask: u8) -> condition::MaskedEq {
+    condition::MaskedEq::new(mask, mask)
+}
+
+/// Check that the high bit of each byte is set
+///
+/// This is [`any_bits`] with the mask `0x80`. These are the bytes outside of ASCII, such as each
+/// byte of a multi-byte UTF-8 character, and the bytes a signed comparison treats as negative.
+///
+/// # Example
+///
+/// ```
+/// use swift_check::{high_bit_set, for_all_ensure, search, not};
+///
+/// let input = "h\u{e9}llo".as_bytes();
+/// assert_eq!(search(input, high_bit_set()), Some(1));
+/// assert!(for_all_ensure(b"hello world", not(high_bit_set())));
+/// ```
+#[inline(always)]
+pub const fn high_bit_set() -> condition::AnyBits {
+    any_bits(0x80)
 }
 
 /// Negate a condition
@@ -407,8 +1086,8 @@ pub const fn eq(expected: u8) -> impl Fn(Vector) -> Vector {
 /// assert!(!should_fail);
 /// ```
 #[inline(always)]
-pub const fn not(cond: impl Fn(Vector) -> Vector) -> impl Fn(Vector) -> Vector {
-    move |data| unsafe { arch::not(cond(data)) }
+pub const fn not<C: Condition>(cond: C) -> condition::Not<C> {
+    condition::Not::new(cond)
 }
 
 /// Combine two conditions
@@ -436,11 +1115,8 @@ pub const fn not(cond: impl Fn(Vector) -> Vector) -> impl Fn(Vector) -> Vector {
 /// assert!(!should_fail);
 /// ```
 #[inline(always)]
-pub const fn and(
-    a: impl Fn(Vector) -> Vector,
-    b: impl Fn(Vector) -> Vector
-) -> impl Fn(Vector) -> Vector {
-    move |data| unsafe { arch::and(a(data), b(data)) }
+pub const fn and<A: Condition, B: Condition>(a: A, b: B) -> condition::And<A, B> {
+    condition::And::new(a, b)
 }
 
 /// Check that either condition is met
@@ -468,11 +1144,8 @@ pub const fn and(
 /// assert!(!should_fail);
 /// ```
 #[inline(always)]
-pub const fn or(
-    a: impl Fn(Vector) -> Vector,
-    b: impl Fn(Vector) -> Vector
-) -> impl Fn(Vector) -> Vector {
-    move |data| unsafe { arch::or(a(data), b(data)) }
+pub const fn or<A: Condition, B: Condition>(a: A, b: B) -> condition::Or<A, B> {
+    condition::Or::new(a, b)
 }
 
 /// Check that only one of the conditions are met
@@ -500,28 +1173,73 @@ pub const fn or(
 /// assert!(!should_fail);
 /// ```
 #[inline(always)]
-pub const fn xor(
-    a: impl Fn(Vector) -> Vector,
-    b: impl Fn(Vector) -> Vector
-) -> impl Fn(Vector) -> Vector {
-    move |data| unsafe { arch::xor(a(data), b(data)) }
+pub const fn xor<A: Condition, B: Condition>(a: A, b: B) -> condition::Xor<A, B> {
+    condition::Xor::new(a, b)
 }
 
-#[macro_export]
-#[doc(hidden)]
-macro_rules! __all {
-    ($left:expr $(,)?) => {
-        $left
-    };
-    ($left:expr, $right:expr $(,)?) => {
-        $crate::arch::and($left, $right)
-    };
-    ($left:expr, $right:expr, $($rest:expr),+ $(,)?) => {
-        $crate::arch::and(
-            $crate::__all!($left, $right),
-            $crate::__all!($($rest),+)
-        )
-    };
+/// Check the byte `N` positions before each byte, rather than the byte itself
+///
+/// Combined with conditions on the byte itself this checks its neighbours, such as a newline not
+/// preceded by a carriage return. The scans carry the bytes of each vector into the next, so a
+/// byte is seen by the one `N` positions after it even across vectors.
+///
+/// The first `N` bytes of the input have nothing before them, so never meet the condition. The
+/// same goes for the first `N` bytes of a vector evaluated on its own, as by [`ensure!`] and
+/// [`find!`]. A [`StreamScanner`] carries the end of each chunk into the next, so only the start
+/// of the stream has nothing before it.
+///
+/// Requirements check each vector on their own and may be given the input in chunks, so a
+/// condition which looks behind cannot be used in one, it does not implement [`NoLookbehind`].
+///
+/// # Arguments
+///
+/// * `N` - How many bytes back to look, from `1` up to `15`. Nesting `prev` looks further back,
+///   up to 16 bytes in total, scanning with a condition which looks back further panics.
+/// * `cond` - The condition the earlier byte must meet, conditions which are not `Copy` can be
+///   passed with [`Condition::by_ref`]
+///
+/// # Example
+///
+/// ```
+/// use swift_check::{prev, search, search_all, and, eq, not, range};
+///
+/// // a newline which is not part of a "\r\n"
+/// let bare_newline = and(eq(b'\n'), not(prev::<1, _>(eq(b'\r'))));
+/// assert_eq!(search(b"first\r\nsecond\r\nthird\nfourth", bare_newline), Some(20));
+///
+/// // a letter directly after a digit
+/// let unit = and(range!(b'a'..=b'z'), prev::<1, _>(range!(b'0'..=b'9')));
+/// let found: Vec<usize> = search_all(b"took 25ms over 3 tries, 10s total", unit).collect();
+/// assert_eq!(found, [7, 26]);
+/// ```
+///
+/// [`NoLookbehind`]: condition::NoLookbehind
+#[inline(always)]
+pub const fn prev<const N: usize, C: Condition + Copy>(cond: C) -> condition::Prev<N, C> {
+    condition::Prev::new(cond)
+}
+
+/// Give a condition a name, used in place of its own description in diagnostics
+///
+/// # Arguments
+///
+/// * `name` - What the condition checks for, read as "expected {name}"
+/// * `cond` - The condition being named
+///
+/// # Example
+///
+/// ```
+/// use swift_check::{validate_expecting, named, any, range, Condition};
+///
+/// let hex = named("hex digit", any!(range!(b'0'..=b'9'), range!(b'a'..=b'f')));
+/// assert_eq!(hex.description().to_string(), "hex digit");
+///
+/// let err = validate_expecting(b"c0ffee-cafe", hex).unwrap_err();
+/// assert_eq!(err.to_string(), "invalid byte 0x2d at offset 6, expected hex digit");
+/// ```
+#[inline(always)]
+pub const fn named<C: Condition>(name: &'static str, cond: C) -> condition::Named<C> {
+    condition::Named::new(name, cond)
 }
 
 /// Check that all the conditions hold
@@ -553,35 +1271,9 @@ macro_rules! all {
     ($left:expr $(,)? ) => {
         $left
     };
-    // Two arguments: directly apply AND between them
-    ($left:expr, $right:expr $(,)?) => {
-        |data: $crate::arch::Vector| -> $crate::arch::Vector {
-            #[allow(unused_unsafe)]
-            unsafe { $crate::__all!($left(data), $right(data)) }
-        }
-    };
-    ($left:expr, $right:expr, $($rest:expr),+ $(,)?) => {
-        |data: $crate::arch::Vector| -> $crate::arch::Vector {
-            #[allow(unused_unsafe)]
-            unsafe { $crate::__all!($left(data), $right(data), $($rest(data)),+) }
-        }
-    };
-}
-
-#[doc(hidden)] #[macro_export]
-macro_rules! __or {
-    ($left:expr) => {
-        // or against nothing, so just return left
-        $left
-    };
-    ($left:expr, $right:expr $(,)?) => {
-        $crate::arch::or($left, $right)
-    };
-    ($left:expr, $right:expr, $($rest:expr),+ $(,)?) => {
-        $crate::arch::or(
-            $crate::__or!($left, $right),
-            $crate::__or!($($rest),+)
-        )
+    // More arguments: AND the first with all of the rest
+    ($left:expr, $($rest:expr),+ $(,)?) => {
+        $crate::and($left, $crate::all!($($rest),+))
     };
 }
 
@@ -617,59 +1309,18 @@ macro_rules! any {
         // already be a higher order function
         $left
     };
-    ($left:expr, $right:expr $(,)?) => {
-        |data: $crate::arch::Vector| -> $crate::arch::Vector {
-            #[allow(unused_unsafe)]
-            unsafe { $crate::__or!($left(data), $right(data)) }
-        }
-    };
-    ($left:expr, $right:expr, $($rest:expr),+ $(,)?) => {
-        |data: $crate::arch::Vector| -> $crate::arch::Vector {
-            #[allow(unused_unsafe)]
-            unsafe { $crate::__or!($left(data), $right(data), $($rest(data)),+) }
-        }
-    }
-}
-
-#[doc(hidden)] #[macro_export]
-macro_rules! __xor {
-    ($left:expr $(,)?) => {
-        $left
-    };
-    ($left:expr, $right:expr $(,)?) => {
-        $crate::arch::xor($left, $right)
-    };
-    ($left:expr, $right:expr, $($rest:expr),+ $(,)?) => {
-        $crate::arch::xor(
-            $crate::__xor!($left, $right),
-            $crate::__xor!($($rest),+)
-        )
-    };
-}
-
-#[macro_export] #[doc(hidden)]
-macro_rules! __one_of {
-    ($l_i:ident: $left:expr, $r_i:ident: $right:expr, $($rest:ident: $cond:expr),* $(,)?) => {
-        |data: $crate::arch::Vector| -> $crate::arch::Vector {
-            #[allow(unused_unsafe)]
-            unsafe {
-                let ($l_i, $r_i, $($rest),+) = ($left(data), $right(data), $($cond(data)),+);
-                // combine xor and nand to ensure only one cond held, this property is ensured for
-                // up to 4 conditions.
-                $crate::arch::and(
-                    $crate::__xor!($l_i, $r_i, $($rest),+),
-                    $crate::arch::not($crate::__all!($l_i, $r_i, $($rest),+))
-                )
-            }
-        }
+    ($left:expr, $($rest:expr),+ $(,)?) => {
+        $crate::or($left, $crate::any!($($rest),+))
     };
 }
 
-/// Ensure only one of the conditions are true
+/// Ensure exactly one of the conditions is true
+///
+/// This is [`exactly!`] with `k` of one, so any number of conditions may be given.
 ///
 /// # Arguments
 ///
-/// * `condition`, ... - The conditions to check, only allowing one to hold (up to 4)
+/// * `condition`, ... - The conditions to check, only allowing one to hold
 ///
 /// # Example
 ///
@@ -690,20 +1341,107 @@ macro_rules! __one_of {
 /// ```
 #[macro_export]
 macro_rules! one_of {
-    ($first:expr $(,)?) => {
-        $first
+    ($($cond:expr),+ $(,)?) => {
+        $crate::exactly!(1; $($cond),+)
     };
-    ($first:expr, $second:expr $(,)?) => {
-        |data: $crate::arch::Vector| -> $crate::arch::Vector {
-            #[allow(unused_unsafe)]
-            unsafe { $crate::__xor!($first(data), $second(data)) }
-        }
+}
+
+/// Ensure exactly `k` of the conditions are true
+///
+/// Each lane counts the conditions it meets, so any number of conditions, up to 255, may be
+/// given, more fail to compile. Each condition costs its own evaluation plus four instructions.
+///
+/// # Arguments
+///
+/// * `k` - The number of conditions which must hold
+/// * `condition`, ... - The conditions to count
+///
+/// # Example
+///
+/// ```
+/// use swift_check::{exactly, for_all_ensure, search, eq, range};
+///
+/// // in the range and exactly one other: even digits and `a`
+/// let cond = exactly!(2; range!(b'0'..=b'f'), range!(b'0'..=b'9'), eq(b'1'), eq(b'3'), eq(b'a'));
+/// assert!(for_all_ensure(b"02468", cond));
+/// assert_eq!(search(b"a13", swift_check::not(cond)), Some(1));
+/// ```
+#[macro_export]
+macro_rules! exactly {
+    ($k:expr; $($cond:expr),+ $(,)?) => {
+        $crate::condition::Exactly::new($k, $crate::__tally!($($cond),+))
+    };
+}
+
+/// Ensure at least `k` of the conditions are true
+///
+/// As with [`exactly!`], any number of conditions, up to 255, may be given.
+///
+/// # Arguments
+///
+/// * `k` - The fewest conditions which must hold
+/// * `condition`, ... - The conditions to count
+///
+/// # Example
+///
+/// ```
+/// use swift_check::{at_least, for_all_ensure, eq, range};
+///
+/// let two_of_three = at_least!(2; range!(b'a'..=b'z'), range!(b'a'..=b'f'), eq(b'a'));
+/// assert!(for_all_ensure(b"abcdef", two_of_three));
+/// assert!(!for_all_ensure(b"xyz", two_of_three));
+/// ```
+#[macro_export]
+macro_rules! at_least {
+    ($k:expr; $($cond:expr),+ $(,)?) => {
+        $crate::condition::AtLeast::new($k, $crate::__tally!($($cond),+))
     };
-    ($first:expr, $second:expr, $third:expr $(,)?) => {
-        $crate::__one_of!(first: $first, second: $second, third: $third)
+}
+
+/// Ensure at most `k` of the conditions are true
+///
+/// As with [`exactly!`], any number of conditions, up to 255, may be given.
+///
+/// # Arguments
+///
+/// * `k` - The most conditions which may hold
+/// * `condition`, ... - The conditions to count
+///
+/// # Example
+///
+/// ```
+/// use swift_check::{at_most, for_all_ensure, eq, range};
+///
+/// let overlap = at_most!(1; range!(b'a'..=b'm'), range!(b'h'..=b'z'), eq(b'_'));
+/// assert!(for_all_ensure(b"abc_xyz", overlap));
+/// assert!(!for_all_ensure(b"hijklm", overlap));
+/// ```
+#[macro_export]
+macro_rules! at_most {
+    ($k:expr; $($cond:expr),+ $(,)?) => {
+        $crate::condition::AtMost::new($k, $crate::__tally!($($cond),+))
     };
-    ($first:expr, $second:expr, $third:expr, $fourth:expr $(,)?) => {
-        $crate::__one_of!(first: $first, second: $second, third: $third, fourth: $fourth)
+}
+
+/// Build the list of conditions counted by [`exactly!`], [`at_least!`] and [`at_most!`]
+#[doc(hidden)] #[macro_export]
+macro_rules! __tally {
+    () => {
+        ()
+    };
+    // grouped eight at a time, keeping both the expansion and the nesting of the types within the
+    // recursion limit for 255 conditions
+    (
+        $a:expr, $b:expr, $c:expr, $d:expr, $e:expr, $f:expr, $g:expr, $h:expr
+        $(, $rest:expr)+
+    ) => {
+        $crate::condition::Group(
+            $crate::__tally!($a, $b, $c, $d, $e, $f, $g, $h),
+            $crate::__tally!($($rest),+)
+        )
+    };
+    ($cond:expr $(, $rest:expr)*) => {
+        ($cond, $crate::__tally!($($rest),*))
     };
 }
 
@@ -723,6 +1461,47 @@ mod tests {
         assert!(res);
     }
 
+    fn table_where(pred: impl Fn(u8) -> bool) -> [bool; 256] {
+        let mut table = [false; 256];
+        for byte in 0..=255u8 { table[byte as usize] = pred(byte); }
+        table
+    }
+
+    macro_rules! exhaustive_range {
+        ($($min:literal..=$max:literal),* ; $($x_min:literal..$x_max:literal),*) => {{
+            $(assert_eq!(
+                truth_table(range!($min..=$max)), table_where(|byte| ($min as u16..=$max as u16).contains(&(byte as u16))),
+                "range!({}..={})", $min, $max
+            );)*
+            $(assert_eq!(
+                truth_table(range!($x_min..$x_max)), table_where(|byte| (byte as u16) > $x_min && (byte as u16) < $x_max),
+                "range!({}..{})", $x_min, $x_max
+            );)*
+        }};
+    }
+
+    macro_rules! exhaustive_cmp {
+        ($($bound:literal),*) => {{
+            $(
+                assert_eq!(truth_table(range!(< $bound)), table_where(|byte| (byte as u16) < $bound));
+                assert_eq!(truth_table(range!(<= $bound)), table_where(|byte| (byte as u16) <= $bound));
+                assert_eq!(truth_table(range!(> $bound)), table_where(|byte| (byte as u16) > $bound));
+                assert_eq!(truth_table(range!(>= $bound)), table_where(|byte| (byte as u16) >= $bound));
+            )*
+        }};
+    }
+
+    #[test]
+    #[allow(unused_comparisons)] // the bounds include 0 and 255
+    fn ranges_are_exact() {
+        exhaustive_range!(
+            0..=0, 0..=127, 0..=128, 0..=255, 1..=254, 10..=245, 127..=128, 128..=255, 200..=254,
+            255..=255, b'0'..=b'9', b'a'..=b'z';
+            0..1, 0..2, 0..255, 1..254, 127..128, 127..129, 126..129, 128..130, 254..255, 10..245
+        );
+        exhaustive_cmp!(0, 1, 64, 127, 128, 129, 200, 254, 255);
+    }
+
     macro_rules! one_of_eq {
         ($($lit:literal),* $(,)?) => {
             one_of!($(eq($lit)),*)
@@ -807,6 +1586,66 @@ mod tests {
         )));
     }
 
+    /// Count how many of eight thresholds each byte is below, each threshold a condition
+    macro_rules! below_thresholds {
+        ($count:ident!($k:expr)) => {
+            truth_table($count!($k;
+                range!(< 8), range!(< 16), range!(< 32), range!(< 64),
+                range!(< 96), range!(< 128), range!(< 192), range!(< 255)
+            ))
+        };
+    }
+
+    #[test]
+    fn counts_any_number_of_conditions() {
+        let count = |byte: u8| [8, 16, 32, 64, 96, 128, 192, 255].iter().filter(|t| (byte as u16) < **t).count();
+        for k in 0..=9u8 {
+            let expected = k as usize;
+            assert_eq!(below_thresholds!(exactly!(k)), table_where(|byte| count(byte) == expected), "exactly {k}");
+            assert_eq!(below_thresholds!(at_least!(k)), table_where(|byte| count(byte) >= expected), "at least {k}");
+            assert_eq!(below_thresholds!(at_most!(k)), table_where(|byte| count(byte) <= expected), "at most {k}");
+        }
+    }
+
+    #[test]
+    fn counts_non_canonical_masks() {
+        // only the highest and lowest bits of each lane, one of which each backend's move mask
+        // reads, as x86 reads the highest, the fallback and wasm the lowest, and NEON a nibble
+        let partial = |data: arch::Vector| unsafe {
+            arch::and(arch::eq(data, arch::splat(b'a')), arch::splat(0x81))
+        };
+        assert_eq!(truth_table(partial), table_where(|byte| byte == b'a'));
+        assert_eq!(truth_table(exactly!(1; partial)), truth_table(partial));
+        assert_eq!(truth_table(at_least!(2; partial, partial, eq(b'b'))), truth_table(partial));
+        assert_eq!(truth_table(at_most!(0; partial, eq(b'b'))), truth_table(not(any!(partial, eq(b'b')))));
+    }
+
+    #[test]
+    fn one_of_has_no_arity_limit() {
+        let input = arch::load(b"abcdefghijklmnop");
+        assert!(ensure!(input, one_of!(eq(b'a'), eq(b'b'), eq(b'c'), eq(b'd'), eq(b'e'), eq(b'f'),
+            eq(b'g'), eq(b'h'), eq(b'i'), eq(b'j'), eq(b'k'), eq(b'l'), eq(b'm'), eq(b'n'), eq(b'o'), eq(b'p'))));
+        // five conditions all holding is odd, which xor alone would accept
+        assert!(!ensure!(input, one_of!(range!(>= 0), range!(>= 0), range!(>= 0), range!(>= 0), range!(>= 0))));
+    }
+
+    #[test]
+    fn prev_looks_back_16_bytes_in_total() {
+        for pos in 0..80 {
+            let mut input = [b'x'; 80];
+            input[pos] = b'a';
+            let found = Some(pos + 16).filter(|end| *end < input.len());
+            assert_eq!(search(&input, prev::<15, _>(prev::<1, _>(eq(b'a')))), found, "'a' at {pos}");
+            assert_eq!(rsearch(&input, prev::<8, _>(prev::<8, _>(eq(b'a')))), found, "'a' at {pos}");
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "at most 16 bytes in total")]
+    fn prev_rejects_looking_back_17_bytes() {
+        let _ = search(&[b'x'; 80], prev::<9, _>(prev::<8, _>(eq(b'a'))));
+    }
+
     macro_rules! ensure_test {
         (
             $input:expr, $condition:expr,
@@ -919,6 +1758,149 @@ mod tests {
                 !has_digits
             }
         }
+        fn search_all_finds_each(s: Vec<u8>, offset: u8) -> bool {
+            // offset the input so that the unaligned prologue is exercised
+            let input = &s[(offset as usize % 16).min(s.len())..];
+            search_all(input, any!(eq(b'a'), range!(200..=255))).eq(
+                input.iter().enumerate()
+                    .filter(|(_, byte)| matches!(byte, b'a' | 200..=255))
+                    .map(|(pos, _)| pos)
+            )
+        }
+        fn rsearch_finds_last(s: Vec<u8>, offset: u8) -> bool {
+            let input = &s[(offset as usize % 16).min(s.len())..];
+            checks!(
+                rsearch(input, eq(b'a')) == input.iter().rposition(|byte| *byte == b'a'),
+                rsearch(input, range!(0..=127)) == input.iter().rposition(|byte| byte.is_ascii()),
+                rsearch(input, not(eq(0))) == input.iter().rposition(|byte| *byte != 0)
+            )
+        }
+        fn count_matches_scalar(s: Vec<u8>, offset: u8) -> bool {
+            let input = &s[(offset as usize % 16).min(s.len())..];
+            checks!(
+                count(input, eq(0)) == input.iter().filter(|byte| **byte == 0).count(),
+                count(input, range!(0..=127)) == input.iter().filter(|byte| byte.is_ascii()).count(),
+                count(input, range!(>= 0)) == input.len()
+            )
+        }
+        fn validate_reports_first_invalid(s: Vec<u8>, offset: u8) -> bool {
+            let input = &s[(offset as usize % 16).min(s.len())..];
+            let expected = input.iter().position(|byte| !byte.is_ascii())
+                .map(|pos| Invalid::new(pos, input[pos]));
+            validate(input, range!(0..=127)).err() == expected
+                && validate_expecting(input, range!(0..=127)).err().map(|err| err.invalid()) == expected
+        }
+        fn span_and_trim_match_scalar(s: Vec<u8>, offset: u8) -> bool {
+            let input = &s[(offset as usize % 16).min(s.len())..];
+            let is_low = |byte: &u8| *byte < 64;
+            let start = input.iter().position(|byte| !is_low(byte)).unwrap_or(input.len());
+            let end = input.iter().rposition(|byte| !is_low(byte)).map_or(0, |pos| pos + 1);
+            checks!(
+                span(input, range!(< 64)) == start,
+                trim_start(input, range!(< 64)) == &input[start..],
+                trim_end(input, range!(< 64)) == &input[..end],
+                trim(input, range!(< 64)) == &input[start..end.max(start)]
+            )
+        }
+        fn search_seq_matches_scalar(s: Vec<u8>, offset: u8) -> bool {
+            // a small alphabet so the sequence occurs often
+            let s: Vec<u8> = s.into_iter().map(|byte| b"01:a"[byte as usize % 4]).collect();
+            let input = &s[(offset as usize % 16).min(s.len())..];
+            let is_digit = |byte: u8| byte.is_ascii_digit();
+            let pattern: [&dyn Fn(u8) -> bool; 5] = [
+                &is_digit, &is_digit, &|byte| byte == b':', &is_digit, &is_digit
+            ];
+            let expected = |len: usize| input.windows(len).position(|window| {
+                window.iter().zip(&pattern).all(|(byte, is)| is(*byte))
+            });
+            let (digit, colon, not_a) = (range!(b'0'..=b'9'), eq(b':'), not(eq(b'a')));
+            checks!(
+                search_seq(input, (digit,)) == expected(1),
+                search_seq(input, (digit, digit)) == expected(2),
+                search_seq(input, (digit, digit, colon)) == expected(3),
+                search_seq(input, (digit, digit, colon, digit, digit)) == expected(5),
+                // as far back as a sequence reaches
+                search_seq(input, (
+                    not_a, not_a, not_a, not_a, not_a, not_a, not_a, not_a,
+                    not_a, not_a, not_a, not_a, not_a, not_a, not_a, not_a
+                )) == input.windows(16).position(|window| !window.contains(&b'a'))
+            )
+        }
+        fn search_seq_looks_behind(s: Vec<u8>, offset: u8) -> bool {
+            let s: Vec<u8> = s.into_iter().map(|byte| b"ab:"[byte as usize % 3]).collect();
+            let input = &s[(offset as usize % 16).min(s.len())..];
+            // "b:" after an 'a', but not two bytes after another 'a'
+            let conds = (
+                and(eq(b'b'), prev::<1, _>(eq(b'a'))), and(eq(b':'), not(prev::<4, _>(eq(b'a'))))
+            );
+            let expected = (1..input.len().saturating_sub(1)).find(|&pos| {
+                input[pos - 1] == b'a' && input[pos] == b'b' && input[pos + 1] == b':'
+                    && (pos < 3 || input[pos - 3] != b'a')
+            });
+            search_seq(input, conds) == expected
+        }
+        fn prev_matches_scalar(s: Vec<u8>, offset: u8) -> bool {
+            // a small alphabet so the neighbours match often
+            let s: Vec<u8> = s.into_iter().map(|byte| b"\r\na1"[byte as usize % 4]).collect();
+            let input = &s[(offset as usize % 16).min(s.len())..];
+            let back = |pos: usize, n: usize, byte: u8| pos >= n && input[pos - n] == byte;
+
+            macro_rules! scans_match {
+                ($cond:expr, |$pos:ident| $expected:expr) => {{
+                    let expected: Vec<usize> = (0..input.len()).filter(|&$pos| $expected).collect();
+                    search_all(input, $cond).eq(expected.iter().copied())
+                        && search(input, $cond) == expected.first().copied()
+                        && rsearch(input, $cond) == expected.last().copied()
+                        && count(input, $cond) == expected.len()
+                        && span(input, not($cond)) == expected.first().copied().unwrap_or(input.len())
+                        && for_all_ensure(input, not($cond)) == expected.is_empty()
+                        && for_all_ensure_ct(input, not($cond)) == expected.is_empty()
+                }};
+            }
+            checks!(
+                scans_match!(
+                    and(eq(b'\n'), not(prev::<1, _>(eq(b'\r')))),
+                    |pos| input[pos] == b'\n' && !back(pos, 1, b'\r')
+                ),
+                scans_match!(prev::<5, _>(eq(b'a')), |pos| back(pos, 5, b'a')),
+                scans_match!(prev::<15, _>(not(eq(b'1'))), |pos| pos >= 15 && input[pos - 15] != b'1'),
+                scans_match!(
+                    prev::<3, _>(and(eq(b'1'), prev::<2, _>(eq(b'a')))),
+                    |pos| back(pos, 3, b'1') && back(pos, 5, b'a')
+                ),
+                // the inner condition holds for the zeroes shifted in before the input
+                scans_match!(
+                    prev::<1, _>(and(not(eq(b'a')), prev::<2, _>(not(eq(b'a'))))),
+                    |pos| pos >= 3 && input[pos - 1] != b'a' && input[pos - 3] != b'a'
+                ),
+                scans_match!(
+                    exactly!(1; eq(b'a'), prev::<1, _>(eq(b'a'))),
+                    |pos| (input[pos] == b'a') != back(pos, 1, b'a')
+                )
+            )
+        }
+        fn search_classify_matches_scalar(s: Vec<u8>, offset: u8) -> bool {
+            // a small alphabet so each class is found often, and the classes overlap
+            let s: Vec<u8> = s.into_iter().map(|byte| b"abcdefgh"[byte as usize % 8]).collect();
+            let input = &s[(offset as usize % 16).min(s.len())..];
+            let classes = (eq(b'a'), range!(b'a'..=b'b'), and(eq(b'c'), prev::<1, _>(eq(b'c'))));
+            let scalar: [&dyn Fn(usize) -> bool; 3] = [
+                &|pos| input[pos] == b'a',
+                &|pos| matches!(input[pos], b'a'..=b'b'),
+                &|pos| pos > 0 && input[pos] == b'c' && input[pos - 1] == b'c'
+            ];
+            let expected = (0..input.len()).find_map(|pos| {
+                scalar.iter().position(|is| is(pos)).map(|class| (pos, class))
+            });
+            checks!(
+                search_classify(input, classes) == expected,
+                search_classify(input, (classes.2,)) == (0..input.len())
+                    .find(|pos| scalar[2](*pos)).map(|pos| (pos, 0)),
+                // without looking behind, which AVX2 scans
+                search_classify(input, (classes.1, classes.0)) == (0..input.len())
+                    .find(|pos| scalar[1](*pos)).map(|pos| (pos, 0))
+            )
+        }
         fn always_holds(s: String) -> bool {
             for_all_ensure(s.as_bytes(), range!(0..=255))
         }
@@ -1165,7 +2147,9 @@ mod tests {
 
 #[cfg(test)]
 mod mirai_tests {
-    use crate::{eq, for_all_ensure, for_all_ensure_ct, search};
+    use crate::{
+        eq, not, for_all_ensure, for_all_ensure_ct, search, search_all, rsearch, count, validate
+    };
 
     #[test]
     fn simple_search() {
@@ -1182,5 +2166,26 @@ mod mirai_tests {
 
         let res = for_all_ensure(input, eq(b' '));
         assert!(!res);
+
+        let mut res = search_all(input, eq(b'j'));
+        assert_eq!(res.next(), Some(64));
+        assert_eq!(res.next(), Some(75));
+        assert_eq!(res.next(), Some(83));
+        assert_eq!(res.next(), Some(90));
+        assert_eq!(res.next(), Some(97));
+        assert_eq!(res.next(), None);
+
+        let res = rsearch(input, eq(b'I')).unwrap();
+        assert_eq!(res, 0);
+
+        let res = rsearch(input, eq(b'j')).unwrap();
+        assert_eq!(res, 97);
+
+        let res = count(input, eq(b'j'));
+        assert_eq!(res, 5);
+
+        let res = validate(input, not(eq(b'j'))).unwrap_err();
+        assert_eq!(res.offset(), 64);
+        assert_eq!(res.value(), b'j');
     }
 }
\ No newline at end of file
@@ -0,0 +1,291 @@
+//! Substring search
+//!
+//! Candidates are found a vector at a time by comparing each position against the two bytes of
+//! the needle expected to be rarest in the haystack, each at its offset within the needle. Only
+//! positions where both match are compared in full, which for most needles is rare enough that
+//! the scan runs at the speed of the two comparisons.
+
+use crate::{arch, search, eq};
+use crate::arch::{load_at, MoveMask, Vector};
+
+/// How common each byte is in typical haystacks, mostly text, higher being more common
+static FREQUENCY: [u8; 256] = {
+    let (mut table, mut byte) = ([0; 256], 0);
+    while byte < 256 {
+        table[byte] = frequency(byte as u8);
+        byte += 1;
+    }
+    table
+};
+
+/// How common the `byte` is, see [`FREQUENCY`]
+const fn frequency(byte: u8) -> u8 {
+    match byte {
+        b' ' | 0 | b'e' | b't' | b'a' | b'o' | b'i' | b'n' => 6,
+        b's' | b'r' | b'h' | b'l' | b'd' | b'c' | b'u' | b'\n' | 0xFF | b'.' | b',' | b'-' | b'/'
+            | b'"' | b'=' | b':' | b'_' | b'\r' | b'\t' => 5,
+        b'a'..=b'z' | b'0'..=b'9' | b'<' | b'>' => 4,
+        b'A'..=b'Z' | b'!'..=b'~' => 3,
+        0..=0x7F => 2,
+        _ => 1
+    }
+}
+
+/// A needle prepared for searching any number of haystacks
+///
+/// The two bytes of the needle which are expected to be rarest are chosen once, when the `Finder`
+/// is created, rather than for each search.
+///
+/// # Example
+///
+/// ```
+/// use swift_check::Finder;
+///
+/// let end_of_head = Finder::new(b"\r\n\r\n");
+///
+/// assert_eq!(end_of_head.find(b"GET / HTTP/1.1\r\nHost: example.com\r\n\r\nbody"), Some(33));
+/// assert_eq!(end_of_head.find(b"GET / HTTP/1.1\r\nHost: exam"), None);
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct Finder<'n> {
+    needle: &'n [u8],
+    /// The offsets within the needle of the two rarest bytes, equal for needles of a single byte
+    rare: (usize, usize),
+    /// The bytes at the `rare` offsets, splat across a vector
+    splat: (Vector, Vector)
+}
+
+impl<'n> Finder<'n> {
+    /// Create a new `Finder` for the `needle`
+    #[inline] #[must_use]
+    pub fn new(needle: &'n [u8]) -> Self {
+        // the first of the rarest bytes, then the first of the rarest other bytes, as a second
+        // comparison against the same byte rules out far fewer positions
+        let rarest = |skip: Option<u8>| (0..needle.len())
+            .filter(|&offset| Some(needle[offset]) != skip)
+            .min_by_key(|&offset| FREQUENCY[needle[offset] as usize]);
+        let first = rarest(None).unwrap_or(0);
+        let second = rarest(needle.get(first).copied())
+            // every byte is the same, any other offset will do
+            .unwrap_or(if needle.len() > 1 { (first + 1) % needle.len() } else { first });
+        let rare = (first, second);
+        let byte = |offset: usize| needle.get(offset).copied().unwrap_or(0);
+        let splat = unsafe { (arch::splat(byte(rare.0)), arch::splat(byte(rare.1))) };
+        Self { needle, rare, splat }
+    }
+
+    /// The needle this `Finder` searches for
+    #[inline] #[must_use]
+    pub const fn needle(&self) -> &'n [u8] {
+        self.needle
+    }
+
+    /// Find the first occurrence of the needle in the `haystack`
+    ///
+    /// # Returns
+    ///
+    /// - `Some(position)` - The position the first occurrence of the needle starts at. An empty
+    ///   needle is found at position `0`.
+    /// - `None` - The needle does not occur within the `haystack`.
+    #[inline]
+    pub fn find(&self, haystack: &[u8]) -> Option<usize> {
+        let len = self.needle.len();
+        match len {
+            0 => return Some(0),
+            1 => return search(haystack, eq(self.needle[0])),
+            _ if len > haystack.len() => return None,
+            _ => {}
+        }
+
+        // the number of positions the needle could start at
+        let count = haystack.len() - len + 1;
+
+        if count < arch::WIDTH {
+            let mask = unsafe { MoveMask::new(arch::and(
+                arch::eq(arch::load_partial(&haystack[self.rare.0..self.rare.0 + count], count), self.splat.0),
+                arch::eq(arch::load_partial(&haystack[self.rare.1..self.rare.1 + count], count), self.splat.1)
+            )) };
+            return self.confirm(haystack, 0, mask.lanes_below(count as u32));
+        }
+
+        let mut offset = 0;
+        while offset + arch::WIDTH <= count {
+            // SAFETY: the last byte loaded is at most `offset + len - 1 + arch::WIDTH - 1`, which
+            // is below `count + len - 1`, the length of the haystack.
+            let mask = unsafe { self.candidates(haystack, offset) };
+            if let Some(pos) = self.confirm(haystack, offset, mask) { return Some(pos); }
+            offset += arch::WIDTH;
+        }
+
+        if offset < count {
+            // load the remaining positions `arch::WIDTH` back from the end, so it overlaps with
+            // what has already been scanned.
+            let tail = count - arch::WIDTH;
+            let mask = unsafe { self.candidates(haystack, tail) };
+            return self.confirm(haystack, tail, mask.lanes_from((offset - tail) as u32));
+        }
+
+        None
+    }
+
+    /// Iterate over each non-overlapping occurrence of the needle in the `haystack`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use swift_check::Finder;
+    ///
+    /// let boundary = Finder::new(b"--boundary");
+    /// let body = b"--boundary\r\nfirst\r\n--boundary\r\nsecond\r\n--boundary--";
+    ///
+    /// assert!(boundary.find_iter(body).eq([0, 19, 39]));
+    /// ```
+    #[inline]
+    pub const fn find_iter<'h>(&self, haystack: &'h [u8]) -> FindIter<'h, 'n> {
+        FindIter { finder: *self, haystack, pos: 0 }
+    }
+
+    /// The positions starting at `offset` where the two rare bytes of the needle match
+    ///
+    /// # Safety
+    ///
+    /// `offset + needle.len() - 1 + arch::WIDTH` must not exceed the length of the `haystack`
+    #[inline(always)]
+    unsafe fn candidates(&self, haystack: &[u8], offset: usize) -> MoveMask {
+        MoveMask::new(arch::and(
+            arch::eq(load_at(haystack, offset + self.rare.0), self.splat.0),
+            arch::eq(load_at(haystack, offset + self.rare.1), self.splat.1)
+        ))
+    }
+
+    /// Compare the needle against each candidate, returning the first which matches in full
+    #[inline(always)]
+    fn confirm(&self, haystack: &[u8], base: usize, mut mask: MoveMask) -> Option<usize> {
+        let len = self.needle.len();
+        while mask.any_bit_set() {
+            let pos = base + mask.trailing_zeros() as usize;
+            if haystack[pos..pos + len] == *self.needle {
+                return Some(pos);
+            }
+            mask = mask.clear_lowest();
+        }
+        None
+    }
+}
+
+/// An iterator over each non-overlapping occurrence of a needle
+///
+/// This is created by [`Finder::find_iter`].
+#[derive(Debug, Clone)]
+pub struct FindIter<'h, 'n> {
+    finder: Finder<'n>,
+    haystack: &'h [u8],
+    pos: usize
+}
+
+impl<'h, 'n> Iterator for FindIter<'h, 'n> {
+    type Item = usize;
+
+    #[inline]
+    fn next(&mut self) -> Option<usize> {
+        let rest = self.haystack.get(self.pos..)?;
+        match self.finder.find(rest) {
+            Some(found) => {
+                let at = self.pos + found;
+                // an empty needle occurs at every position, so always make progress
+                self.pos = at + self.finder.needle.len().max(1);
+                Some(at)
+            },
+            None => {
+                self.pos = self.haystack.len() + 1;
+                None
+            }
+        }
+    }
+}
+
+impl<'h, 'n> core::iter::FusedIterator for FindIter<'h, 'n> {}
+
+/// Find the first occurrence of the `needle` in the `haystack`
+///
+/// When searching for the same needle repeatedly, prefer a [`Finder`].
+///
+/// # Arguments
+///
+/// * `haystack` - The data to search
+/// * `needle`   - The bytes to search for
+///
+/// # Returns
+///
+/// - `Some(position)` - The position the first occurrence of the `needle` starts at. An empty
+///   `needle` is found at position `0`.
+/// - `None` - The `needle` does not occur within the `haystack`.
+///
+/// # Example
+///
+/// ```
+/// use swift_check::find_substring;
+///
+/// let page = b"<script>let x = 1;</script><p>hello</p>";
+///
+/// assert_eq!(find_substring(page, b"</script>"), Some(18));
+/// assert_eq!(find_substring(page, b"</div>"), None);
+/// ```
+#[inline]
+pub fn find_substring(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    Finder::new(needle).find(haystack)
+}
+
+#[cfg(all(test, not(mirai)))]
+mod tests {
+    use super::*;
+    use quickcheck::quickcheck;
+
+    extern crate alloc;
+    use alloc::vec::Vec;
+
+    /// Random needles rarely occur in random haystacks, take the needle from the haystack and
+    /// shrink the alphabet so near misses are common
+    fn needle_of(haystack: &[u8], at: u8, len: u8) -> Vec<u8> {
+        let start = (at as usize).min(haystack.len());
+        let end = (start + len as usize % 24).min(haystack.len());
+        haystack[start..end].to_vec()
+    }
+
+    fn small_alphabet(s: Vec<u8>) -> Vec<u8> {
+        s.into_iter().map(|byte| b'a' + byte % 3).collect()
+    }
+
+    quickcheck! {
+        fn find_substring_matches_memmem(s: Vec<u8>, at: u8, len: u8, flip: bool) -> bool {
+            let s = small_alphabet(s);
+            let mut needle = needle_of(&s, at, len);
+            if flip && !needle.is_empty() { needle[0] = b'd'; }
+            find_substring(&s, &needle) == memchr::memmem::find(&s, &needle)
+        }
+        fn find_iter_matches_memmem(s: Vec<u8>, at: u8, len: u8) -> bool {
+            let s = small_alphabet(s);
+            let needle = needle_of(&s, at, len);
+            Finder::new(&needle).find_iter(&s).eq(memchr::memmem::find_iter(&s, &needle))
+        }
+    }
+
+    #[test]
+    fn find_substring_edges() {
+        assert_eq!(find_substring(b"", b""), Some(0));
+        assert_eq!(find_substring(b"", b"a"), None);
+        assert_eq!(find_substring(b"ab", b"abc"), None);
+        assert_eq!(find_substring(b"aaaaaaaaaaaaaaaaaaaaab", b"ab"), Some(20));
+        assert!(Finder::new(b"").find_iter(b"ab").eq([0, 1, 2]));
+    }
+
+    #[test]
+    fn finder_picks_rare_bytes() {
+        assert_eq!(Finder::new(b"the quick Z").rare, (10, 4));
+        assert_eq!(Finder::new(b"--boundary").rare, (2, 9));
+        assert_eq!(Finder::new(b"\r\n\r\n").rare, (0, 1));
+        assert_eq!(Finder::new(b"aaa").rare, (0, 1));
+        assert_eq!(Finder::new(b"a").rare, (0, 0));
+        assert_eq!(Finder::new(b"").rare, (0, 0));
+    }
+}
@@ -0,0 +1,209 @@
+//! Conditions from regular expression character classes
+//!
+//! Validation rules are often already written as character classes, such as `[\w.-]` or
+//! `[^\x00-\x1f]`. [`from_regex_class`] parses one with [`regex-syntax`] and turns it into a
+//! [`ByteClass`], usable anywhere a condition is.
+//!
+//! Classes are matched against bytes, not characters: `\w`, `\d` and `\s` are their ASCII
+//! definitions, `\xNN` is the byte `NN`, and negated classes include the bytes `0x80..=0xFF`.
+//!
+//! [`regex-syntax`]: https://docs.rs/regex-syntax
+
+use alloc::boxed::Box;
+use crate::{ByteClass, ByteSet};
+use regex_syntax::hir::{self, Class, Hir, HirKind};
+use regex_syntax::ParserBuilder;
+
+/// Why a class could not be turned into a condition
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ClassError {
+    /// The pattern is not valid regex syntax
+    Parse(Box<regex_syntax::Error>),
+    /// The pattern is valid, but not a single character class, such as `[a-z]+`
+    NotAClass,
+    /// The class holds characters which are not a single byte, such as `[é]` or `(?u)[aé]`
+    NotBytes
+}
+
+impl core::fmt::Display for ClassError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            Self::Parse(err) => write!(f, "invalid character class: {err}"),
+            Self::NotAClass => f.write_str("pattern is not a single character class"),
+            Self::NotBytes => f.write_str("character class holds characters wider than a byte")
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ClassError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Parse(err) => Some(&**err),
+            _ => None
+        }
+    }
+}
+
+impl From<regex_syntax::Error> for ClassError {
+    #[inline]
+    fn from(err: regex_syntax::Error) -> Self {
+        Self::Parse(Box::new(err))
+    }
+}
+
+/// Parse a regular expression character class into a condition
+///
+/// The `class` may be a bracketed class (`[a-z_]`, `[^,]`), a Perl class (`\w`, `\S`), `.`, or a
+/// single byte (`a`, `\x00`). Anything matching more than one byte, or any character outside of
+/// ASCII, is rejected. Bytes outside of ASCII are written as escapes, such as `\xE9`.
+///
+/// # Errors
+///
+/// - [`ClassError::Parse`] if the `class` is not valid regex syntax.
+/// - [`ClassError::NotAClass`] if the `class` matches anything other than a single byte.
+/// - [`ClassError::NotBytes`] if the `class` holds a character encoded as more than one byte,
+///   with or without `(?u)`.
+///
+/// # Example
+///
+/// ```
+/// use swift_check::{from_regex_class, for_all_ensure, search};
+///
+/// let name = from_regex_class(r"[\w.-]")?;
+/// assert!(for_all_ensure(b"my-config_name.v2", name));
+///
+/// let printable = from_regex_class(r"[^\x00-\x1f]")?;
+/// assert_eq!(search(b"line\nbreak", swift_check::not(printable)), Some(4));
+///
+/// assert!(from_regex_class(r"[a-z]+").is_err());
+/// # Ok::<(), swift_check::regex::ClassError>(())
+/// ```
+pub fn from_regex_class(class: &str) -> Result<ByteClass, ClassError> {
+    let hir = ParserBuilder::new()
+        .unicode(false)
+        .utf8(false)
+        .build()
+        .parse(class)
+        .map_err(|err| match &err {
+            // without `(?u)` the parser refuses characters outside of ASCII
+            regex_syntax::Error::Translate(inner)
+                if *inner.kind() == hir::ErrorKind::UnicodeNotAllowed => ClassError::NotBytes,
+            _ => ClassError::from(err)
+        })?;
+    byte_set(&hir).map(|set| set.compile())
+}
+
+/// The bytes a single byte pattern matches
+fn byte_set(hir: &Hir) -> Result<ByteSet, ClassError> {
+    let mut set = ByteSet::new();
+    match hir.kind() {
+        HirKind::Class(Class::Bytes(class)) => {
+            for range in class.iter() {
+                set.insert_range(range.start()..=range.end());
+            }
+        },
+        HirKind::Class(Class::Unicode(class)) => {
+            for range in class.iter() {
+                if !range.end().is_ascii() { return Err(ClassError::NotBytes); }
+                set.insert_range(range.start() as u8..=range.end() as u8);
+            }
+        },
+        HirKind::Literal(lit) if lit.0.len() == 1 => set.insert(lit.0[0]),
+        // a class of one character is parsed as that character, which may be encoded as several
+        // bytes
+        HirKind::Literal(lit) => return Err(match core::str::from_utf8(&lit.0) {
+            Ok(text) if text.chars().count() == 1 => ClassError::NotBytes,
+            _ => ClassError::NotAClass
+        }),
+        _ => return Err(ClassError::NotAClass)
+    }
+    Ok(set)
+}
+
+#[cfg(all(test, not(mirai)))]
+mod tests {
+    use super::*;
+    use crate::{for_all_ensure, search, truth_table, Condition};
+
+    fn members(class: &str) -> ByteSet {
+        from_regex_class(class).unwrap().byte_set()
+    }
+
+    fn table_where(pred: impl Fn(u8) -> bool) -> [bool; 256] {
+        let mut table = [false; 256];
+        for byte in 0..=255u8 { table[byte as usize] = pred(byte); }
+        table
+    }
+
+    #[test]
+    fn perl_classes_are_ascii() {
+        let word = ByteSet::from(table_where(|byte| byte.is_ascii_alphanumeric() || byte == b'_'));
+        assert_eq!(members(r"\w"), word);
+        assert_eq!(members(r"[\w]"), word);
+        assert_eq!(members(r"\d").to_string(), "[0-9]");
+        assert_eq!(members(r"[\w.-]").len(), word.len() + 2);
+    }
+
+    #[test]
+    fn negation_includes_high_bytes() {
+        let printable = members(r"[^\x00-\x1f]");
+        assert_eq!(printable.len(), 256 - 0x20);
+        assert!(printable.contains(0xFF));
+        assert!(!printable.contains(b'\n'));
+        assert_eq!(members(r"\xff").to_string(), r"[\xFF]");
+    }
+
+    #[test]
+    fn class_is_a_condition() {
+        let class = from_regex_class(r"[^,\n]").unwrap();
+        let table = truth_table(class);
+        assert!((0..=255u8).all(|b| table[b as usize] == class.eval_byte(b)));
+
+        let data = b"some long field which is wider than a vector,next";
+        assert_eq!(search(data, crate::not(class)), Some(44));
+        assert!(for_all_ensure(&data[..44], class));
+    }
+
+    #[cfg(feature = "require")]
+    #[test]
+    fn class_is_a_requirement() {
+        use crate::require::{check, Requirement, Requires};
+        use crate::requirements;
+
+        let digit = from_regex_class(r"\d").unwrap();
+        let (_, res) = check(
+            b"version 2 of the configuration file",
+            requirements!([number = Requires::new(digit, || "There needs to be a digit!")])
+        ).result();
+        assert!(res.is_ok());
+
+        let (_, res) = check(
+            b"version two of the configuration file",
+            requirements!([number = Requires::new(digit, || "There needs to be a digit!")])
+        ).result();
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn rejects_wide_characters_without_unicode() {
+        assert_eq!(from_regex_class("[é]"), Err(ClassError::NotBytes));
+        assert_eq!(from_regex_class("[^aé]"), Err(ClassError::NotBytes));
+        // the byte itself can still be written as an escape
+        assert_eq!(members(r"[\xE9]").to_string(), r"[\xE9]");
+    }
+
+    #[test]
+    fn rejects_non_classes() {
+        assert_eq!(from_regex_class(r"[a-z]+"), Err(ClassError::NotAClass));
+        assert_eq!(from_regex_class("ab"), Err(ClassError::NotAClass));
+        assert_eq!(from_regex_class(""), Err(ClassError::NotAClass));
+        assert_eq!(from_regex_class("(?u)[aé]"), Err(ClassError::NotBytes));
+        assert_eq!(from_regex_class("(?u)[é]"), Err(ClassError::NotBytes));
+        assert_eq!(from_regex_class("é"), Err(ClassError::NotBytes));
+        assert_eq!(from_regex_class("(?u)éa"), Err(ClassError::NotAClass));
+        assert!(matches!(from_regex_class("[a-"), Err(ClassError::Parse(_))));
+        assert_eq!(members("(?u)[a-c]").to_string(), "[a-c]");
+    }
+}
@@ -100,9 +100,54 @@ impl core::ops::Deref for ErrMsg {
 #[cfg(feature = "std")]
 impl std::error::Error for ErrMsg {}
 
-/// A `Condition` is used internally by the [`Requirement`] trait. `requirement!` will expand into
-/// a constant function which returns a type that implements this trait.
-pub trait Condition {
+/// The error of a requirement built with [`expecting`], describing the condition which was never
+/// met
+#[derive(Copy, Clone)]
+pub struct Unmet<C> {
+    cond: C
+}
+
+impl<C: crate::Condition> Unmet<C> {
+    /// Create a new `Unmet` instance for the `cond`
+    #[inline] #[must_use]
+    pub const fn new(cond: C) -> Self {
+        Self { cond }
+    }
+
+    /// The condition which was never met
+    #[inline] #[must_use]
+    pub const fn cond(&self) -> &C {
+        &self.cond
+    }
+}
+
+impl<C: crate::Condition> core::fmt::Display for Unmet<C> {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "expected at least one {}", self.cond.description())
+    }
+}
+
+impl<C: crate::Condition> core::fmt::Debug for Unmet<C> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "Unsatisfied Requirement: {self}")
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<C: crate::Condition> From<Unmet<C>> for alloc::string::String {
+    #[inline]
+    fn from(value: Unmet<C>) -> Self {
+        alloc::string::ToString::to_string(&value)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<C: crate::Condition> std::error::Error for Unmet<C> {}
+
+/// A single requirement, used internally by the [`Requirement`] trait. `requirement!` will expand
+/// into a constant function which returns a type that implements this trait.
+pub trait Required {
     /// The error type associated with the requirement. When used in `requirements` all the error
     /// types must implement `Into` to a common type.
     type Error;
@@ -113,6 +158,11 @@ pub trait Condition {
     fn ok(self) -> Result<(), Self::Error>;
 }
 
+/// The previous name of [`Required`], renamed so it no longer shares its name with
+/// [`swift_check::Condition`](crate::Condition).
+#[deprecated(since = "0.3.0", note = "renamed to `Required`")]
+pub use Required as Condition;
+
 /// A trait representing a collection of conditions which must be met at least once.
 ///
 /// # Methods
@@ -120,7 +170,6 @@ pub trait Condition {
 /// * [`result`] - Check if all requirements were met, fails fast
 /// * [`results`] - Iterate over each requirement's result
 ///
-
 /// [`result`]: Requirement::result
 /// [`results`]: Requirement::results
 pub trait Requirement {
@@ -153,7 +202,6 @@ pub trait Requirement {
     /// 1. `Ok(())` if all requirements were met, or the first error (in order of requirements)
     ///    caught.
     ///
-
     /// [`results`]: Requirement::results
     fn result(self) -> (bool, Result<(), Self::Error>);
     /// # Results
@@ -166,7 +214,6 @@ pub trait Requirement {
     /// 0. A `bool` denoting if all bytes met at least one of the requirements.
     /// 1. An iterator over results, in order of the provided requirements.
     ///
-
     /// [`result`]: Requirement::result
     fn results(self) -> (bool, impl Iterator<Item = Result<(), Self::Error>>);
 }
@@ -183,7 +230,7 @@ pub trait Requirement {
 /// - `Err`: The error to `Raise` if the requirement was not fulfilled
 pub struct Requires<C, Raise, Err>
     where
-        C: Fn(Vector) -> Vector,
+        C: crate::Condition + crate::condition::NoLookbehind,
         Raise: FnOnce() -> Err
 {
     /// The required condition
@@ -196,19 +243,62 @@ pub struct Requires<C, Raise, Err>
 
 impl<C, Raise, Err> Requires<C, Raise, Err>
     where
-        C: Fn(Vector) -> Vector,
+        C: crate::Condition + crate::condition::NoLookbehind,
         Raise: FnOnce() -> Err
 {
     /// Create a new `Requires` instance
+    ///
+    /// Each vector is checked on its own, so the `cond` cannot look behind, as [`prev`] does.
+    ///
+    /// ```compile_fail
+    /// use swift_check::{and, eq, prev, require::Requires};
+    ///
+    /// let escaped_quote = Requires::new(and(eq(b'"'), prev::<1, _>(eq(b'\\'))), || "needs an escaped quote");
+    /// ```
+    ///
+    /// [`prev`]: crate::prev
     #[inline] #[must_use]
     pub const fn new(cond: C, raise: Raise) -> Self {
         Self { cond, raise, seen: false }
     }
 }
 
-impl<C, Raise, Err> Condition for Requires<C, Raise, Err>
+/// A requirement whose error describes the condition, see [`Unmet`]
+///
+/// Where [`ErrMsg`] carries a fixed message for the whole requirement, this reports what was
+/// expected from the description of the `cond`, which [`named`] can make meaningful to the reader.
+///
+/// # Example
+///
+/// ```
+/// use swift_check::{
+///     require::{Requirement, check, expecting},
+///     requirements,
+///     named, range, eq
+/// };
+///
+/// let (_, res) = check(
+///     b"hunter2",
+///     requirements!(String, [
+///         digit = expecting(named("digit", range!(b'0'..=b'9'))),
+///         symbol = expecting(eq(b'!'))
+///     ])
+/// ).result();
+///
+/// assert_eq!(res.unwrap_err(), "expected at least one '!'");
+/// ```
+///
+/// [`named`]: crate::named
+#[inline] #[must_use]
+pub const fn expecting<C: crate::Condition + crate::condition::NoLookbehind + Copy>(
+    cond: C
+) -> Requires<C, impl FnOnce() -> Unmet<C>, Unmet<C>> {
+    Requires::new(cond, move || Unmet::new(cond))
+}
+
+impl<C, Raise, Err> Required for Requires<C, Raise, Err>
     where
-        C: Fn(Vector) -> Vector,
+        C: crate::Condition + crate::condition::NoLookbehind,
         Raise: FnOnce() -> Err
 {
     type Error = Err;
@@ -219,9 +309,9 @@ impl<C, Raise, Err> Condition for Requires<C, Raise, Err>
     ///
     /// The `MoveMask` used to extract the condition result, used to check validity of the input,
     /// ensuring each byte fulfilled at least one condition.
-    #[inline] #[must_use]
+    #[inline]
     fn check(&mut self, vector: Vector) -> MoveMask {
-        let mask = unsafe { MoveMask::new((self.cond)(vector)) };
+        let mask = unsafe { MoveMask::new(self.cond.eval_vector(vector)) };
         self.seen |= mask.any_bit_set();
         mask
     }
@@ -318,7 +408,7 @@ macro_rules! requirement {
     ) => {
         $(#[$attr])*
         #[must_use]
-        $vis const fn $req_name () -> impl $crate::require::Condition<Error = $crate::require::ErrMsg> {
+        $vis const fn $req_name () -> impl $crate::require::Required<Error = $crate::require::ErrMsg> {
             let res = $crate::require::Requires::new($cond, || { $crate::require::ErrMsg::new($error_message) });
             res
         }
@@ -329,7 +419,7 @@ macro_rules! requirement {
     ) => {
         $(#[$attr])*
         #[must_use]
-        $vis const fn $req_name () -> impl $crate::require::Condition<Error = $err_ty> {
+        $vis const fn $req_name () -> impl $crate::require::Required<Error = $err_ty> {
             let res = $crate::require::Requires::new($cond, || { $create_err });
             res
         }
@@ -340,7 +430,7 @@ macro_rules! requirement {
     ) => {
         $(#[$attr])*
         #[must_use]
-        $vis const fn $req_name () -> impl $crate::require::Condition<Error = $err> {
+        $vis const fn $req_name () -> impl $crate::require::Required<Error = $err> {
             let res = $crate::require::Requires::new($cond, || { $err ($($args),*) });
             res
         }
@@ -351,7 +441,7 @@ macro_rules! requirement {
     ) => {
         $(#[$attr])*
         #[must_use]
-        $vis const fn $req_name () -> impl $crate::require::Condition<Error = $err> {
+        $vis const fn $req_name () -> impl $crate::require::Required<Error = $err> {
             let res = $crate::require::Requires::new($cond, || { $err :: $func ($($args),*) });
             res
         }
@@ -362,7 +452,7 @@ macro_rules! requirement {
     ) => {
         $(#[$attr])*
         #[must_use]
-        $vis const fn $req_name () -> impl $crate::require::Condition<Error = $err> {
+        $vis const fn $req_name () -> impl $crate::require::Required<Error = $err> {
             let res = $crate::require::Requires::new($cond, || { $err :: $variant });
             res
         }
@@ -413,34 +503,63 @@ macro_rules! requirement {
 /// ```txt
 /// [Requirements, ...]
 /// ```
+///
+/// # Runtime Requirements
+///
+/// `requirement!` expands into a constant function, so its condition must be known at compile
+/// time. For conditions built at runtime, such as from a [`ByteSet`], a requirement can instead be
+/// given as `name = value`, where the value is a [`Requires`] instance.
+///
+/// ```
+/// use swift_check::{
+///     require::{Requirement, Requires, check},
+///     requirement, requirements,
+///     ByteSet, eq
+/// };
+///
+/// requirement!(pub space => eq(b' ') =>! "There needs to be a space!");
+///
+/// // loaded at startup
+/// let symbols = ByteSet::from_bytes(b"!?#");
+///
+/// let (valid, res) = check(
+///     b"hello world!",
+///     requirements!([space, symbol = Requires::new(symbols.compile(), || "There needs to be a symbol!")])
+/// ).result();
+///
+/// assert!(!valid);
+/// assert!(res.is_ok());
+/// ```
+///
+/// [`ByteSet`]: crate::ByteSet
 #[macro_export]
 macro_rules! requirements {
-    ([$($requirement:ident),* $(,)?] $(,)?) => {
-        $crate::requirements!($crate::require::ErrMsg, [$($requirement),*])
+    ([$($requirement:ident $(= $value:expr)?),* $(,)?] $(,)?) => {
+        $crate::requirements!($crate::require::ErrMsg, [$($requirement $(= $value)?),*])
     };
-    ($error:ty, [$($requirement:ident),* $(,)?] $(,)?) => {{
+    ($error:ty, [$($requirement:ident $(= $value:expr)?),* $(,)?] $(,)?) => {{
         #[allow(non_camel_case_types)]
-        struct Requirements<$($requirement: $crate::require::Condition),*> {
+        struct Requirements<$($requirement: $crate::require::Required),*> {
             __valid: bool,
             $($requirement: $requirement),*
         }
         #[allow(non_camel_case_types)]
         impl<$($requirement),*> $crate::require::Requirement for Requirements<$($requirement),*>
             where
-                $($requirement: $crate::require::Condition,
-                <$requirement as $crate::require::Condition>::Error: Into<$error>),*
+                $($requirement: $crate::require::Required,
+                <$requirement as $crate::require::Required>::Error: Into<$error>),*
         {
             type Error = $error;
             #[inline]
             fn check(&mut self, vector: $crate::arch::Vector) {
                 #[allow(unused_imports)]
-                use $crate::require::Condition as _;
+                use $crate::require::Required as _;
                 self.__valid &= ($(self.$requirement.check(vector) )|*).all_bits_set();
             }
             #[inline]
             fn check_partial(&mut self, vector: $crate::arch::Vector, len: u32) {
                 #[allow(unused_imports)]
-                use $crate::require::Condition as _;
+                use $crate::require::Required as _;
                 self.__valid &= ($(self.$requirement.check(vector) )|*)
                     .trailing_ones() >= len;
             }
@@ -465,11 +584,18 @@ macro_rules! requirements {
 
         Requirements {
             __valid: true,
-            $($requirement: $requirement ()),*
+            $($requirement: $crate::__requirement!($requirement $(= $value)?)),*
         }
     }};
 }
 
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __requirement {
+    ($requirement:ident) => { $requirement () };
+    ($requirement:ident = $value:expr) => { $value };
+}
+
 /// Check that all `requirement!`s are fulfilled
 ///
 /// # Arguments
@@ -502,10 +628,35 @@ macro_rules! requirements {
 /// // case this is false.
 /// assert!(!valid);
 /// ```
+///
+/// # Streaming
+///
+/// As the state of the requirements is passed back, data arriving in chunks can be checked by
+/// passing each chunk in turn.
+///
+/// ```
+/// # use swift_check::{
+/// #     require::{Requirement, check},
+/// #     requirement, requirements,
+/// #     eq
+/// # };
+/// #
+/// # requirement!(pub space => eq(b' ') =>! "There needs to be a space!");
+/// # requirement!(pub seven => eq(b'7') =>! "There needs to be a seven!");
+/// #
+/// let mut req = requirements!([space, seven]);
+/// for chunk in [b"example".as_slice(), b" input", b" 7"] {
+///     req = check(chunk, req);
+/// }
+///
+/// let (valid, res) = req.result();
+/// assert!(!valid);
+/// assert!(res.is_ok());
+/// ```
 #[inline]
 pub fn check<R: Requirement>(data: &[u8], mut req: R) -> R {
     if data.len() >= arch::WIDTH {
-        unsafe { arch::scan::ensure_requirements(data, req) }
+        unsafe { dispatch!(ensure_requirements(data, req)) }
     } else {
         let len = data.len();
         req.check_partial(unsafe { arch::load_partial(data, len) }, len as u32);
@@ -569,4 +720,4 @@ fn test() {
 
     // or if you just want to know if an err took place you can use ok
     println!("{:?}", res.result().1.unwrap_err());
-}
\ No newline at end of file
+}
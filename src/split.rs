@@ -0,0 +1,255 @@
+//! Split data on the bytes meeting a condition
+//!
+//! These mirror `split`, `splitn` and `split_inclusive` on slices, though the delimiters are found
+//! via [`search_all`], so the scan's state is kept between each field rather than restarting the
+//! search for every delimiter.
+
+use crate::{search_all, Condition, Matches};
+
+/// An iterator over the subslices of data separated by bytes meeting a condition
+///
+/// This is created by [`split`].
+pub struct Split<'a, F> {
+    data: &'a [u8],
+    matches: Matches<'a, F>,
+    start: usize,
+    finished: bool
+}
+
+impl<'a, F: Condition> Split<'a, F> {
+    #[inline]
+    fn new(data: &'a [u8], cond: F) -> Self {
+        Self { data, matches: search_all(data, cond), start: 0, finished: false }
+    }
+
+    /// Yield the remainder of the data, regardless of any delimiters it may contain
+    #[inline]
+    fn finish(&mut self) -> Option<&'a [u8]> {
+        if self.finished {
+            None
+        } else {
+            self.finished = true;
+            Some(&self.data[self.start..])
+        }
+    }
+}
+
+impl<'a, F: Condition> Iterator for Split<'a, F> {
+    type Item = &'a [u8];
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a [u8]> {
+        if self.finished { return None; }
+        match self.matches.next() {
+            Some(pos) => {
+                let field = &self.data[self.start..pos];
+                self.start = pos + 1;
+                Some(field)
+            },
+            None => self.finish()
+        }
+    }
+}
+
+impl<'a, F: Condition> core::iter::FusedIterator for Split<'a, F> {}
+
+/// An iterator over the subslices of data separated by bytes meeting a condition, limited to a
+/// given number of splits
+///
+/// This is created by [`splitn`].
+pub struct SplitN<'a, F> {
+    inner: Split<'a, F>,
+    count: usize
+}
+
+impl<'a, F: Condition> Iterator for SplitN<'a, F> {
+    type Item = &'a [u8];
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a [u8]> {
+        match self.count {
+            0 => None,
+            1 => {
+                self.count = 0;
+                self.inner.finish()
+            },
+            _ => {
+                self.count -= 1;
+                self.inner.next()
+            }
+        }
+    }
+}
+
+impl<'a, F: Condition> core::iter::FusedIterator for SplitN<'a, F> {}
+
+/// An iterator over the subslices of data terminated by bytes meeting a condition, each subslice
+/// includes its terminator
+///
+/// This is created by [`split_inclusive`].
+pub struct SplitInclusive<'a, F> {
+    data: &'a [u8],
+    matches: Matches<'a, F>,
+    start: usize,
+    finished: bool
+}
+
+impl<'a, F: Condition> Iterator for SplitInclusive<'a, F> {
+    type Item = &'a [u8];
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a [u8]> {
+        if self.finished { return None; }
+        match self.matches.next() {
+            Some(pos) => {
+                let field = &self.data[self.start..=pos];
+                self.start = pos + 1;
+                Some(field)
+            },
+            None => {
+                self.finished = true;
+                // unlike `split`, data ending with a terminator does not yield a trailing empty
+                // subslice
+                if self.start < self.data.len() { Some(&self.data[self.start..]) } else { None }
+            }
+        }
+    }
+}
+
+impl<'a, F: Condition> core::iter::FusedIterator for SplitInclusive<'a, F> {}
+
+/// Split the data on each byte meeting the `cond`
+///
+/// # Arguments
+///
+/// * `data` - The data to split
+/// * `cond` - The condition a delimiter must meet
+///
+/// # Returns
+///
+/// An iterator over the subslices between each delimiter, the delimiters themselves are not
+/// included. Like `<[u8]>::split`, empty subslices are yielded for leading, trailing, or adjacent
+/// delimiters.
+///
+/// # Example
+///
+/// ```
+/// use swift_check::{split, any, eq};
+///
+/// let input = b"name,age;location,,occupation";
+/// let mut fields = split(input, any!(eq(b','), eq(b';')));
+///
+/// assert_eq!(fields.next(), Some(b"name".as_slice()));
+/// assert_eq!(fields.next(), Some(b"age".as_slice()));
+/// assert_eq!(fields.next(), Some(b"location".as_slice()));
+/// assert_eq!(fields.next(), Some(b"".as_slice()));
+/// assert_eq!(fields.next(), Some(b"occupation".as_slice()));
+/// assert_eq!(fields.next(), None);
+/// ```
+#[inline]
+pub fn split<F: Condition>(data: &[u8], cond: F) -> Split<'_, F> {
+    Split::new(data, cond)
+}
+
+/// Split the data on each byte meeting the `cond`, yielding at most `n` subslices
+///
+/// # Arguments
+///
+/// * `data` - The data to split
+/// * `n`    - The maximum number of subslices to yield, the last of which contains the remainder
+///   of `data`
+/// * `cond` - The condition a delimiter must meet
+///
+/// # Example
+///
+/// ```
+/// use swift_check::{splitn, eq};
+///
+/// let header = b"Content-Type: text/html: charset=utf-8";
+/// let mut parts = splitn(header, 2, eq(b':'));
+///
+/// assert_eq!(parts.next(), Some(b"Content-Type".as_slice()));
+/// assert_eq!(parts.next(), Some(b" text/html: charset=utf-8".as_slice()));
+/// assert_eq!(parts.next(), None);
+/// ```
+#[inline]
+pub fn splitn<F: Condition>(data: &[u8], n: usize, cond: F) -> SplitN<'_, F> {
+    SplitN { inner: Split::new(data, cond), count: n }
+}
+
+/// Split the data after each byte meeting the `cond`
+///
+/// # Arguments
+///
+/// * `data` - The data to split
+/// * `cond` - The condition a terminator must meet
+///
+/// # Returns
+///
+/// An iterator over the subslices of `data`, each ending with its terminator. Like
+/// `<[u8]>::split_inclusive`, if `data` ends with a terminator there is no trailing empty
+/// subslice.
+///
+/// # Example
+///
+/// ```
+/// use swift_check::{split_inclusive, eq};
+///
+/// let input = b"first line\nsecond line\nunterminated";
+/// let mut lines = split_inclusive(input, eq(b'\n'));
+///
+/// assert_eq!(lines.next(), Some(b"first line\n".as_slice()));
+/// assert_eq!(lines.next(), Some(b"second line\n".as_slice()));
+/// assert_eq!(lines.next(), Some(b"unterminated".as_slice()));
+/// assert_eq!(lines.next(), None);
+/// ```
+#[inline]
+pub fn split_inclusive<F: Condition>(data: &[u8], cond: F) -> SplitInclusive<'_, F> {
+    SplitInclusive { data, matches: search_all(data, cond), start: 0, finished: false }
+}
+
+#[cfg(all(test, not(mirai)))]
+mod tests {
+    use super::*;
+    use crate::{any, eq};
+    use quickcheck::quickcheck;
+
+    extern crate alloc;
+    use alloc::vec::Vec;
+
+    fn is_delim(byte: &u8) -> bool {
+        matches!(byte, b',' | b';')
+    }
+
+    /// Random bytes rarely contain delimiters, replace a portion of them
+    fn with_delims(s: Vec<u8>) -> Vec<u8> {
+        s.into_iter().map(|byte| match byte % 8 {
+            0 => b',',
+            1 => b';',
+            _ => byte
+        }).collect()
+    }
+
+    quickcheck! {
+        fn split_matches_slice(s: Vec<u8>) -> bool {
+            let s = with_delims(s);
+            split(&s, any!(eq(b','), eq(b';'))).eq(s.split(is_delim))
+        }
+        fn splitn_matches_slice(s: Vec<u8>, n: u8) -> bool {
+            let (s, n) = (with_delims(s), n as usize % 8);
+            splitn(&s, n, any!(eq(b','), eq(b';'))).eq(s.splitn(n, is_delim))
+        }
+        fn split_inclusive_matches_slice(s: Vec<u8>) -> bool {
+            let s = with_delims(s);
+            split_inclusive(&s, any!(eq(b','), eq(b';'))).eq(s.split_inclusive(is_delim))
+        }
+    }
+
+    #[test]
+    fn split_edges() {
+        assert!(split(b"", eq(b',')).eq([b"".as_slice()]));
+        assert!(split(b",", eq(b',')).eq([b"".as_slice(), b"".as_slice()]));
+        assert_eq!(split_inclusive(b"", eq(b',')).next(), None);
+        assert_eq!(splitn(b"a,b", 0, eq(b',')).next(), None);
+    }
+}
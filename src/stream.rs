@@ -0,0 +1,345 @@
+//! Scan data which arrives in chunks
+//!
+//! Each chunk is scanned independently, only the state relevant to the stream as a whole (the
+//! number of bytes seen, the first invalid byte, and for conditions which look behind the last
+//! bytes seen) is carried between them. Chunks of any length may be pushed, those shorter than the
+//! register width go through the same partial loading as the rest of the crate.
+
+use crate::{arch, not, search, search_all, validate, Condition, Invalid, Matches};
+use crate::arch::MoveMask;
+use crate::condition::{eval_in_context, ByRef, Context};
+
+/// Search and validate a stream of chunks with a single condition
+///
+/// Every offset reported by the `StreamScanner` is relative to the start of the stream rather
+/// than the chunk it was found in. Conditions which look behind, such as [`prev`], see the end of
+/// the previous chunk from the start of the next.
+///
+/// # Example
+///
+/// ```
+/// use swift_check::{StreamScanner, any, eq, range};
+///
+/// let chunks: [&[u8]; 3] = [b"GET /index.html HTTP/1.1\r\n", b"Host: exa", b"mple.com\r\n"];
+///
+/// let mut newlines = StreamScanner::new(eq(b'\n'));
+/// let mut found = Vec::new();
+/// for chunk in chunks {
+///     found.extend(newlines.search_all(chunk));
+/// }
+/// assert_eq!(found, [25, 44]);
+///
+/// let mut printable = StreamScanner::new(any!(range!(0x20..=0x7E), eq(b'\r'), eq(b'\n')));
+/// for chunk in chunks {
+///     printable.validate(chunk).unwrap();
+/// }
+///
+/// let err = printable.validate(b"\x00").unwrap_err();
+/// assert_eq!(err.offset(), 45);
+/// assert_eq!(printable.position(), 46);
+/// ```
+///
+/// [`prev`]: crate::prev
+pub struct StreamScanner<F> {
+    cond: F,
+    /// The number of bytes which have been pushed through the scanner
+    position: usize,
+    /// The first byte of the stream which did not meet the condition
+    invalid: Option<Invalid>,
+    /// The last bytes of the stream, ending with the last byte seen, kept if the condition looks
+    /// behind
+    behind: [u8; arch::WIDTH]
+}
+
+/// The start of a chunk, evaluated with the end of the stream before it, see
+/// [`StreamScanner::head`]
+struct Head {
+    /// The lanes of the first `len` bytes of the chunk which met the condition
+    met: MoveMask,
+    len: usize,
+    /// Where the scan of the rest of the chunk starts, far enough back that the bytes after the
+    /// head see each byte they look back at
+    rest: usize,
+    /// The number of positions the scan of the rest shares with the head
+    skip: usize
+}
+
+impl<F: Condition> StreamScanner<F> {
+    /// Create a new `StreamScanner` at the start of a stream
+    #[inline] #[must_use]
+    pub const fn new(cond: F) -> Self {
+        Self { cond, position: 0, invalid: None, behind: [0; arch::WIDTH] }
+    }
+
+    /// The number of bytes the `StreamScanner` has seen
+    #[inline] #[must_use]
+    pub const fn position(&self) -> usize {
+        self.position
+    }
+
+    /// `true` if each byte seen by [`validate`] met the condition
+    ///
+    /// [`validate`]: StreamScanner::validate
+    #[inline] #[must_use]
+    pub const fn is_valid(&self) -> bool {
+        self.invalid.is_none()
+    }
+
+    /// Advance the stream over the `chunk`, returning the offset of the chunk
+    #[inline(always)]
+    fn advance(&mut self, chunk: &[u8]) -> usize {
+        let base = self.position;
+        self.position += chunk.len();
+        if self.cond.lookbehind() > 0 {
+            if chunk.len() >= arch::WIDTH {
+                self.behind.copy_from_slice(&chunk[chunk.len() - arch::WIDTH..]);
+            } else {
+                self.behind.copy_within(chunk.len().., 0);
+                self.behind[arch::WIDTH - chunk.len()..].copy_from_slice(chunk);
+            }
+        }
+        base
+    }
+
+    /// Evaluate the start of the `chunk` with the bytes of the stream preceding it
+    ///
+    /// Without this the scans of the chunk would take its first bytes as the start of the input,
+    /// with nothing before them. For conditions which do not look behind they are right to, so
+    /// the head is empty and the whole chunk is scanned as is.
+    #[inline]
+    fn head(&self, chunk: &[u8]) -> Head {
+        let lookbehind = self.cond.lookbehind();
+        if lookbehind == 0 {
+            return Head { met: unsafe { MoveMask::new(arch::splat(0)) }, len: 0, rest: 0, skip: 0 };
+        }
+
+        let len = chunk.len().min(arch::WIDTH);
+        let mut bytes = [0u8; 2 * arch::WIDTH];
+        bytes[..arch::WIDTH].copy_from_slice(&self.behind);
+        bytes[arch::WIDTH..arch::WIDTH + len].copy_from_slice(&chunk[..len]);
+
+        // only the bytes which were seen precede the chunk
+        let seen = self.position.min(arch::WIDTH);
+        let bytes = &bytes[arch::WIDTH - seen..];
+        // SAFETY: `bytes` holds the `seen` bytes followed by a full vector
+        let met = unsafe {
+            MoveMask::new(eval_in_context(&self.cond, arch::load_at(bytes, seen), || Context::at(bytes, seen)))
+        }.lanes_below(len as u32);
+
+        // a condition looks back at most 16 bytes, so when the chunk is longer than the head each
+        // byte after it looks back no further than the head.
+        let skip = lookbehind.min(len);
+        Head { met, len, rest: len - skip, skip }
+    }
+
+    /// The first byte of the `chunk` which did not meet the condition, without advancing the
+    /// stream
+    #[inline]
+    fn first_unmet(&self, chunk: &[u8]) -> Option<usize> {
+        let head = self.head(chunk);
+        match head.met.trailing_ones() as usize {
+            unmet if unmet < head.len => return Some(unmet),
+            _ => {}
+        }
+
+        let rest = &chunk[head.rest..];
+        let unmet = if head.skip == 0 {
+            validate(rest, self.cond.by_ref()).err().map(|invalid| invalid.offset())
+        } else {
+            search_all(rest, not(self.cond.by_ref())).find(|pos| *pos >= head.skip)
+        };
+        unmet.map(|pos| head.rest + pos)
+    }
+
+    /// The length of the leading run of bytes in the `chunk` meeting the condition, without
+    /// advancing the stream
+    #[cfg(feature = "std")]
+    #[inline]
+    pub(crate) fn valid_len(&self, chunk: &[u8]) -> usize {
+        self.first_unmet(chunk).unwrap_or(chunk.len())
+    }
+
+    /// Advance the stream over the `chunk` without scanning it, for bytes already known to meet
+    /// the condition
+    #[cfg(feature = "std")]
+    #[inline]
+    pub(crate) fn skip(&mut self, chunk: &[u8]) {
+        self.advance(chunk);
+    }
+
+    /// Find the first byte in the `chunk` meeting the condition
+    ///
+    /// # Returns
+    ///
+    /// - `Some(position)` - The position of the first byte which met the condition, relative to
+    ///   the start of the stream.
+    /// - `None` - There was no circumstance of the condition being met within the `chunk`.
+    #[inline]
+    pub fn search(&mut self, chunk: &[u8]) -> Option<usize> {
+        let head = self.head(chunk);
+        let base = self.advance(chunk);
+        if head.met.any_bit_set() { return Some(base + head.met.trailing_zeros() as usize); }
+
+        let rest = &chunk[head.rest..];
+        let found = if head.skip == 0 {
+            search(rest, self.cond.by_ref())
+        } else {
+            search_all(rest, self.cond.by_ref()).find(|pos| *pos >= head.skip)
+        };
+        found.map(|pos| base + head.rest + pos)
+    }
+
+    /// Find every byte in the `chunk` meeting the condition
+    ///
+    /// # Returns
+    ///
+    /// An iterator yielding the position of each byte which met the condition, relative to the
+    /// start of the stream.
+    #[inline]
+    pub fn search_all<'a>(&'a mut self, chunk: &'a [u8]) -> StreamMatches<'a, F> {
+        let head = self.head(chunk);
+        let base = self.advance(chunk);
+        StreamMatches {
+            head: head.met,
+            matches: search_all(&chunk[head.rest..], self.cond.by_ref()),
+            base,
+            rest: head.rest,
+            skip: head.skip
+        }
+    }
+
+    /// Validate that each byte in the `chunk` meets the condition
+    ///
+    /// Once a byte has failed to meet the condition the stream remains invalid, further chunks
+    /// are not scanned though they still advance the [`position`].
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(())` - Every byte in the stream thus far met the condition.
+    /// - `Err(Invalid)` - The first byte in the stream which did not meet the condition, its
+    ///   offset is relative to the start of the stream.
+    ///
+    /// [`position`]: StreamScanner::position
+    #[inline]
+    pub fn validate(&mut self, chunk: &[u8]) -> Result<(), Invalid> {
+        let unmet = if self.invalid.is_none() { self.first_unmet(chunk) } else { None };
+        let base = self.advance(chunk);
+        if let Some(offset) = unmet {
+            self.invalid = Some(Invalid::new(base + offset, chunk[offset]));
+        }
+        self.result()
+    }
+
+    /// The validation result for the stream thus far, see [`validate`]
+    ///
+    /// [`validate`]: StreamScanner::validate
+    #[inline]
+    pub const fn result(&self) -> Result<(), Invalid> {
+        match self.invalid {
+            Some(invalid) => Err(invalid),
+            None => Ok(())
+        }
+    }
+}
+
+/// An iterator over each position in a chunk where the condition was met, relative to the start
+/// of the stream
+///
+/// This is created by [`StreamScanner::search_all`].
+pub struct StreamMatches<'a, F> {
+    /// The lanes of the head of the chunk yet to be yielded
+    head: MoveMask,
+    /// The matches in the rest of the chunk, the first `skip` of which are within the head
+    matches: Matches<'a, ByRef<'a, F>>,
+    base: usize,
+    rest: usize,
+    skip: usize
+}
+
+impl<'a, F: Condition> Iterator for StreamMatches<'a, F> {
+    type Item = usize;
+
+    #[inline]
+    fn next(&mut self) -> Option<usize> {
+        if self.head.any_bit_set() {
+            let pos = self.head.trailing_zeros() as usize;
+            self.head = self.head.clear_lowest();
+            return Some(self.base + pos);
+        }
+        let skip = self.skip;
+        self.matches.find(|pos| *pos >= skip).map(|pos| self.base + self.rest + pos)
+    }
+}
+
+impl<'a, F: Condition> core::iter::FusedIterator for StreamMatches<'a, F> {}
+
+#[cfg(all(test, not(mirai)))]
+mod tests {
+    use super::*;
+    use crate::{and, eq, prev, range};
+    use quickcheck::quickcheck;
+
+    extern crate alloc;
+    use alloc::vec::Vec;
+
+    /// Split the data into chunks of varying lengths
+    fn chunks(data: &[u8], lens: Vec<u8>) -> Vec<&[u8]> {
+        let mut res = Vec::new();
+        let mut rest = data;
+        for len in lens.into_iter().cycle().take(data.len() + 1) {
+            let (chunk, tail) = rest.split_at((len as usize % 40).min(rest.len()));
+            res.push(chunk);
+            rest = tail;
+        }
+        res.push(rest);
+        res
+    }
+
+    quickcheck! {
+        fn stream_search_all_matches_contiguous(s: Vec<u8>, lens: Vec<u8>) -> bool {
+            let mut scanner = StreamScanner::new(range!(< 32));
+            let mut found = Vec::new();
+            for chunk in chunks(&s, lens) {
+                found.extend(scanner.search_all(chunk));
+            }
+            found.into_iter().eq(search_all(&s, range!(< 32))) && scanner.position() == s.len()
+        }
+        fn stream_search_matches_contiguous(s: Vec<u8>, lens: Vec<u8>) -> bool {
+            let mut scanner = StreamScanner::new(eq(0));
+            let first = chunks(&s, lens).into_iter().find_map(|chunk| scanner.search(chunk));
+            first == search(&s, eq(0))
+        }
+        fn stream_validate_matches_contiguous(s: Vec<u8>, lens: Vec<u8>) -> bool {
+            let mut scanner = StreamScanner::new(range!(>= 8));
+            for chunk in chunks(&s, lens) {
+                let _ = scanner.validate(chunk);
+            }
+            scanner.result() == validate(&s, range!(>= 8))
+                && scanner.is_valid() == scanner.result().is_ok()
+        }
+        fn stream_prev_matches_contiguous(s: Vec<u8>, lens: Vec<u8>) -> bool {
+            // a small alphabet so the neighbours match often
+            let s: Vec<u8> = s.into_iter().map(|byte| b"\r\na1"[byte as usize % 4]).collect();
+            let bare_newline = and(eq(b'\n'), not(prev::<1, _>(eq(b'\r'))));
+            let far = prev::<15, _>(eq(b'a'));
+
+            let (mut newlines, mut first, mut far_all, mut valid) = (
+                StreamScanner::new(bare_newline), StreamScanner::new(far),
+                StreamScanner::new(far), StreamScanner::new(not(bare_newline))
+            );
+            let (mut found, mut found_far, mut found_first) = (Vec::new(), Vec::new(), None);
+            for chunk in chunks(&s, lens) {
+                found.extend(newlines.search_all(chunk));
+                found_far.extend(far_all.search_all(chunk));
+                let in_chunk = first.search(chunk);
+                found_first = found_first.or(in_chunk);
+                let _ = valid.validate(chunk);
+            }
+
+            found.into_iter().eq(search_all(&s, bare_newline))
+                && found_far.into_iter().eq(search_all(&s, far))
+                && found_first == search(&s, far)
+                && valid.result() == validate(&s, not(bare_newline))
+        }
+    }
+}